@@ -1,7 +1,10 @@
-use valor::cgmath::Vector3;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+use valor::cgmath::{InnerSpace, Vector3};
 use valor::simple::{Material as SimpleMaterial, Model, Vertex};
-use valor::{glutin, Handle, ValorBuilder};
-use valor_camera::CameraBuilder;
+use valor::{glutin, Handle, RenderQueue, ValorBuilder};
+use valor_camera::{CameraBuilder, CameraController, FlyController, GlutinInput};
 use valor_scene::Scene;
 
 const LIGHT_BLUE: [f32; 4] = [0.1, 0.2, 0.3, 1.0];
@@ -14,15 +17,27 @@ enum SceneEntry {
     Empty,
 }
 
-pub fn main() {
+pub fn main() -> valor::Result<()> {
     let (mut events_loop, mut renderer) = ValorBuilder::new()
-        .with_title(&"Basic example")
+        .with_title("Basic example")
         .with_dimensions(800.0, 600.0)
         .with_clear_color(LIGHT_BLUE)
         .with_vsync(true)
-        .finish();
+        .finish()?;
 
-    let camera = CameraBuilder::new().finish();
+    let camera = Rc::new(RefCell::new(
+        CameraBuilder::new()
+            .with_aspect_ratio(renderer.width as f32 / renderer.height as f32)
+            .finish(),
+    ));
+
+    // Keep the camera's aspect ratio in line with the window
+    let resized_camera = camera.clone();
+    renderer.on_resize(move |width, height| {
+        resized_camera
+            .borrow_mut()
+            .set_aspect_ratio(width as f32 / height as f32);
+    });
 
     // Create a scene with string ids
     let mut scene = Scene::new(SceneEntry::Empty);
@@ -35,56 +50,95 @@ pub fn main() {
     ];
 
     // Create the material we'll draw with
-    let material = SimpleMaterial::new(&renderer.display);
+    let material = SimpleMaterial::new(renderer.facade())?;
 
     // Create Triangle
-    let triangle = Model::new(&mut renderer, vertices);
+    let triangle = Model::new(&mut renderer, vertices)?;
 
     let triangle_index = scene.create_node(SceneEntry::Model(triangle));
     scene.translate(triangle_index, Vector3::new(0.0, -0.2, -2.0));
     scene.add_child(root, triangle_index);
 
+    // Fly around with WASD once the cursor has been captured by clicking
+    let mut controller = FlyController::new();
+    let mut input = GlutinInput::new();
+    let display = renderer.display().unwrap().clone();
+    let mut last_frame = Instant::now();
+
+    // Draws are collected here while walking the scene, then drawn together
+    let mut queue = RenderQueue::new();
+
     let mut running = true;
     while running {
+        let now = Instant::now();
+        let dt = now.duration_since(last_frame).as_secs_f32();
+        last_frame = now;
+
+        controller.update(dt, &mut camera.borrow_mut());
+
         // Update global constant buffer
-        let view_proj_matrix: [[f32; 4]; 4] = camera.get_view_proj().into();
+        let view_proj_matrix: [[f32; 4]; 4] = camera.borrow().get_view_proj().into();
+        let eye = camera.borrow().position();
 
         // Draw frame
-        renderer.render(|mut target| {
-            use valor::Material;
-
+        renderer.render(|target| {
             // Iterate over the entries in the scene graph
             for (_id, entry, transform) in scene.traverse() {
                 match entry {
                     SceneEntry::Model(ref model) => {
                         // Update locals with transform
+                        let depth = (transform.w.truncate() - eye).magnitude();
                         let u_world: [[f32; 4]; 4] = transform.into();
 
                         // Ensure usage of the correct material here
-                        material.draw(&mut target, model.clone(), view_proj_matrix, u_world);
+                        queue.submit(&material, model.clone(), u_world, depth);
                     }
                     SceneEntry::Empty => {}
                 }
             }
-        });
+
+            queue.flush(target, view_proj_matrix, None)?;
+            Ok(())
+        })?;
 
         // Handle events
-        events_loop.poll_events(|event| match event {
-            glutin::Event::WindowEvent { event, .. } => match event {
-                glutin::WindowEvent::KeyboardInput {
-                    input:
-                        glutin::KeyboardInput {
-                            virtual_keycode: Some(glutin::VirtualKeyCode::Escape),
-                            ..
-                        },
-                    ..
-                }
-                | glutin::WindowEvent::CloseRequested => {
-                    running = false;
+        events_loop.poll_events(|event| {
+            renderer.handle_event(&event);
+
+            if let Some(input) = input.translate(&event) {
+                controller.handle_input(&input);
+            }
+
+            if let glutin::Event::WindowEvent { event, .. } = event {
+                match event {
+                    glutin::WindowEvent::MouseInput {
+                        state: glutin::ElementState::Pressed,
+                        ..
+                    } if !input.is_grabbed() => {
+                        let _ = input.set_grabbed(&display.gl_window(), true);
+                    }
+                    glutin::WindowEvent::KeyboardInput {
+                        input:
+                            glutin::KeyboardInput {
+                                virtual_keycode: Some(glutin::VirtualKeyCode::Escape),
+                                state: glutin::ElementState::Pressed,
+                                ..
+                            },
+                        ..
+                    } => {
+                        // Release the cursor first, then quit on a second press
+                        if input.is_grabbed() {
+                            let _ = input.set_grabbed(&display.gl_window(), false);
+                        } else {
+                            running = false;
+                        }
+                    }
+                    glutin::WindowEvent::CloseRequested => running = false,
+                    _ => {}
                 }
-                _ => {}
-            },
-            _ => {}
+            }
         });
     }
+
+    Ok(())
 }
@@ -1,23 +1,40 @@
-use crate::Renderer;
-use glium;
+use crate::clip::ClipSymbols;
+use crate::{DepthMode, Error, Renderer, Result};
+use glium::glutin::{GlContext, GlProfile, GlRequest};
+use std::os::raw::c_void;
 
 const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
 
+/// The lowest OpenGL version the built-in shaders are written for
+const GL_3_2: GlRequest = GlRequest::GlThenGles {
+    opengl_version: (3, 2),
+    opengles_version: (3, 0),
+};
+
+/// A context configuration to try when creating a window
+struct Attempt {
+    request: GlRequest,
+    profile: Option<GlProfile>,
+    depth_bits: u8,
+    vsync: bool,
+}
+
 /// Configuration object for initializing `Renderer` and window systems.
 ///
 /// # Example
 ///
-/// ```
+/// ```no_run
 /// use valor::ValorBuilder;
 ///
 /// const LIGHT_BLUE: [f32; 4] = [0.1, 0.2, 0.3, 1.0];
 ///
 /// let (mut events_loop, mut renderer) = ValorBuilder::new()
 ///     .with_title(&"Basic example")
-///     .with_dimensions(800, 600)
+///     .with_dimensions(800.0, 600.0)
 ///     .with_clear_color(LIGHT_BLUE)
 ///     .with_vsync(true)
-///     .finish();
+///     .finish()?;
+/// # Ok::<(), valor::Error>(())
 /// ```
 pub struct ValorBuilder {
     title: &'static str,
@@ -25,6 +42,13 @@ pub struct ValorBuilder {
     height: f64,
     vsync: bool,
     clear_color: [f32; 4],
+    depth_mode: DepthMode,
+}
+
+impl Default for ValorBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ValorBuilder {
@@ -36,6 +60,7 @@ impl ValorBuilder {
             height: 480.0,
             vsync: false,
             clear_color: BLACK,
+            depth_mode: DepthMode::Standard,
         }
     }
 
@@ -65,8 +90,21 @@ impl ValorBuilder {
         self
     }
 
-    /// Complete the build
-    pub fn finish(self) -> (glium::glutin::EventsLoop, Renderer) {
+    /// Update the depth buffer layout, which must match the projection of the
+    /// cameras being drawn with. See `DepthMode::Reversed` for what reverse-Z
+    /// needs from the OpenGL context.
+    pub fn with_depth_mode(mut self, depth_mode: DepthMode) -> Self {
+        self.depth_mode = depth_mode;
+        self
+    }
+
+    /// Complete the build.
+    ///
+    /// If the requested context can't be created, progressively less
+    /// demanding ones are tried: an explicit OpenGL 3.2 core profile, then a
+    /// smaller depth buffer without vsync. The error lists why each attempt
+    /// failed.
+    pub fn finish(self) -> Result<(glium::glutin::EventsLoop, Renderer)> {
         // Prepare Glutin builders
         let events_loop = glium::glutin::EventsLoop::new();
         let builder = glium::glutin::WindowBuilder::new()
@@ -75,17 +113,140 @@ impl ValorBuilder {
                 self.width,
                 self.height,
             ));
-        let context = glium::glutin::ContextBuilder::new().with_vsync(self.vsync);
 
-        let window = glium::Display::new(builder, context, &events_loop).unwrap();
+        let attempts = [
+            Attempt {
+                request: GlRequest::Latest,
+                profile: None,
+                depth_bits: 24,
+                vsync: self.vsync,
+            },
+            Attempt {
+                request: GL_3_2,
+                profile: Some(GlProfile::Core),
+                depth_bits: 24,
+                vsync: self.vsync,
+            },
+            Attempt {
+                request: GL_3_2,
+                profile: Some(GlProfile::Core),
+                depth_bits: 16,
+                vsync: false,
+            },
+        ];
 
+        let mut failures = Vec::new();
+        let mut window = None;
+        for attempt in attempts.iter() {
+            let mut context = glium::glutin::ContextBuilder::new()
+                .with_gl(attempt.request)
+                .with_vsync(attempt.vsync)
+                .with_depth_buffer(attempt.depth_bits);
+            if let Some(profile) = attempt.profile {
+                context = context.with_gl_profile(profile);
+            }
+
+            match glium::Display::new(builder.clone(), context, &events_loop) {
+                Ok(display) => {
+                    window = Some(display);
+                    break;
+                }
+                Err(error) => failures.push(error.to_string()),
+            }
+        }
+        let window = window.ok_or(Error::Context(failures))?;
+        let clip_control =
+            ClipSymbols::lookup(|name| window.gl_window().get_proc_address(name) as *const c_void)
+                .load(&window);
+
+        let dpi_factor = window.gl_window().get_hidpi_factor();
         let (width, height): (u32, u32) = match window.gl_window().get_inner_size() {
-            Some(size) => size.into(),
+            Some(size) => size.to_physical(dpi_factor).into(),
             None => (0, 0),
         };
 
-        let renderer = Renderer::new(window, self.clear_color, width, height);
+        let renderer = Renderer::new(
+            window,
+            clip_control,
+            self.clear_color,
+            self.depth_mode,
+            width,
+            height,
+            dpi_factor,
+        );
+
+        Ok((events_loop, renderer))
+    }
+
+    /// Complete the build without opening a window. The renderer draws into
+    /// an offscreen framebuffer the size of the window dimensions, which can
+    /// be read back with `Renderer::read_pixels`.
+    ///
+    /// This needs an OpenGL implementation which can run without a display,
+    /// such as OSMesa on Linux, and fails if none is available.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use valor::ValorBuilder;
+    ///
+    /// let mut renderer = ValorBuilder::new()
+    ///     .with_dimensions(256.0, 256.0)
+    ///     .finish_headless()?;
+    ///
+    /// renderer.render(|target| {
+    ///     // Draw the scene
+    ///     Ok(())
+    /// })?;
+    /// renderer.save_png("frame.png")?;
+    /// # Ok::<(), valor::Error>(())
+    /// ```
+    pub fn finish_headless(self) -> Result<Renderer> {
+        let (width, height) = (self.width as u32, self.height as u32);
+
+        let mut failures = Vec::new();
+        let mut context = None;
+        for &(request, profile) in
+            [(GL_3_2, Some(GlProfile::Core)), (GlRequest::Latest, None)].iter()
+        {
+            let mut builder =
+                glium::glutin::HeadlessRendererBuilder::new(width, height).with_gl(request);
+            if let Some(profile) = profile {
+                builder = builder.with_gl_profile(profile);
+            }
+
+            let created = builder
+                .build()
+                .map_err(|error| error.to_string())
+                .and_then(|headless| {
+                    // glium won't hand out the context's loader once it owns it
+                    unsafe { headless.make_current() }.map_err(|error| error.to_string())?;
+                    let symbols = ClipSymbols::lookup(|name| {
+                        headless.get_proc_address(name) as *const c_void
+                    });
+
+                    let context = glium::HeadlessRenderer::new(headless)
+                        .map_err(|error| error.to_string())?;
+                    let clip_control = symbols.load(&context);
+                    Ok((context, clip_control))
+                });
+            match created {
+                Ok(created) => {
+                    context = Some(created);
+                    break;
+                }
+                Err(error) => failures.push(error),
+            }
+        }
+        let (context, clip_control) = context.ok_or(Error::Context(failures))?;
 
-        (events_loop, renderer)
+        Renderer::new_headless(
+            context,
+            clip_control,
+            self.clear_color,
+            self.depth_mode,
+            width,
+            height,
+        )
     }
 }
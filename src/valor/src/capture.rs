@@ -0,0 +1,129 @@
+use crate::{Error, Image, Renderer, Result};
+use glium::texture::pixel_buffer::PixelBuffer;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+/// Callback receiving a captured frame
+pub(crate) type CaptureCallback = Box<dyn FnOnce(Image)>;
+
+/// A frame which has been copied into a pixel buffer, but may still be in
+/// flight on the GPU.
+pub(crate) struct PendingCapture {
+    /// Where the frame's pixels are being copied to
+    pub pixels: PixelBuffer<(u8, u8, u8, u8)>,
+    /// The index of the frame the capture was taken in
+    pub frame: u64,
+    /// Callbacks waiting for the frame
+    pub callbacks: Vec<CaptureCallback>,
+}
+
+impl PendingCapture {
+    /// Read the frame back, waiting for the copy to finish if it hasn't
+    /// already, and hand it to the callbacks
+    pub fn resolve(self) -> Result<()> {
+        let image: Image = self.pixels.read_as_texture_2d()?;
+
+        for callback in self.callbacks {
+            callback(image.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Records frames as a sequence of numbered PNG files, e.g. for trailers.
+///
+/// The game should advance by `timestep` every frame while recording, rather
+/// than by the time the frame took, so the sequence plays back smoothly at the
+/// recorder's frame rate however long each frame took to draw and save. Frames
+/// are read back through pixel buffers and written on a background thread, so
+/// recording doesn't stall drawing.
+///
+/// # Example
+///
+/// ```no_run
+/// # use valor::{FrameRecorder, ValorBuilder};
+/// # let (_, mut renderer) = ValorBuilder::new().finish()?;
+/// let mut recorder = FrameRecorder::new("frames", 30.0)?;
+///
+/// for _ in 0..300 {
+///     let dt = recorder.timestep();
+///     // Advance the game by `dt`
+///
+///     recorder.record(&mut renderer);
+///     renderer.render(|target| {
+///         // Draw the scene
+///         Ok(())
+///     })?;
+/// }
+///
+/// recorder.finish(&mut renderer)?;
+/// # Ok::<(), valor::Error>(())
+/// ```
+pub struct FrameRecorder {
+    directory: PathBuf,
+    timestep: f32,
+    frames: u32,
+    sender: Sender<(PathBuf, Image)>,
+    writer: JoinHandle<io::Result<()>>,
+}
+
+impl FrameRecorder {
+    /// Start recording into `directory`, which is created if needed, at
+    /// `frames_per_second`
+    pub fn new<P: AsRef<Path>>(directory: P, frames_per_second: f32) -> Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        std::fs::create_dir_all(&directory)?;
+
+        let (sender, receiver) = mpsc::channel::<(PathBuf, Image)>();
+        let writer = thread::spawn(move || {
+            for (path, image) in receiver {
+                image.save_png(path)?;
+            }
+            Ok(())
+        });
+
+        Ok(FrameRecorder {
+            directory,
+            timestep: 1.0 / frames_per_second,
+            frames: 0,
+            sender,
+            writer,
+        })
+    }
+
+    /// The time, in seconds, to advance the game by each recorded frame
+    pub fn timestep(&self) -> f32 {
+        self.timestep
+    }
+
+    /// The number of frames recorded so far
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Record the next frame `renderer` draws
+    pub fn record(&mut self, renderer: &mut Renderer) {
+        let path = self.directory.join(format!("frame_{:05}.png", self.frames));
+        let sender = self.sender.clone();
+        self.frames += 1;
+
+        renderer.capture_next_frame(move |image| {
+            // The writer only stops early if saving failed, which `finish`
+            // reports
+            let _ = sender.send((path, image));
+        });
+    }
+
+    /// Stop recording, waiting for every frame to be read back and written
+    pub fn finish(self, renderer: &mut Renderer) -> Result<()> {
+        renderer.finish_captures()?;
+        drop(self.sender);
+
+        match self.writer.join() {
+            Ok(result) => result.map_err(Error::from),
+            Err(_) => Err(io::Error::other("frame writer thread panicked").into()),
+        }
+    }
+}
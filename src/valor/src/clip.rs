@@ -0,0 +1,120 @@
+use crate::DepthMode;
+use glium::backend::Facade;
+use glium::{Api, Version};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+
+const GL_EXTENSIONS: u32 = 0x1F03;
+const GL_NUM_EXTENSIONS: u32 = 0x821D;
+const GL_LOWER_LEFT: u32 = 0x8CA1;
+const GL_NEGATIVE_ONE_TO_ONE: u32 = 0x935E;
+const GL_ZERO_TO_ONE: u32 = 0x935F;
+
+type ClipControlFn = extern "system" fn(origin: u32, depth: u32);
+type GetIntegervFn = extern "system" fn(name: u32, data: *mut i32);
+type GetStringiFn = extern "system" fn(name: u32, index: u32) -> *const c_char;
+
+/// OpenGL entry points for choosing the clip-space depth range, which glium
+/// has no setting for.
+///
+/// They're looked up through the windowing library while it still owns the
+/// context, since glium keeps its loader private.
+pub(crate) struct ClipSymbols {
+    clip_control: *const c_void,
+    clip_control_ext: *const c_void,
+    get_integerv: *const c_void,
+    get_stringi: *const c_void,
+}
+
+impl ClipSymbols {
+    /// Look up the entry points with `get_proc_address`, which must belong to
+    /// the current context
+    pub(crate) fn lookup<F>(get_proc_address: F) -> Self
+    where
+        F: Fn(&str) -> *const c_void,
+    {
+        ClipSymbols {
+            clip_control: get_proc_address("glClipControl"),
+            clip_control_ext: get_proc_address("glClipControlEXT"),
+            get_integerv: get_proc_address("glGetIntegerv"),
+            get_stringi: get_proc_address("glGetStringi"),
+        }
+    }
+
+    /// Check which entry point, if any, the context behind `facade` supports.
+    /// It must be the context the symbols were looked up for.
+    ///
+    /// OpenGL 4.5 has `glClipControl` built in, and older versions may have
+    /// it through ARB_clip_control. OpenGL ES has EXT_clip_control.
+    pub(crate) fn load<F: Facade + ?Sized>(self, facade: &F) -> Option<ClipControl> {
+        let context = facade.get_context();
+        let version = *context.get_opengl_version();
+        let (core, extension, function) = match version.0 {
+            Api::Gl => (
+                version >= Version(Api::Gl, 4, 5),
+                "GL_ARB_clip_control",
+                self.clip_control,
+            ),
+            Api::GlEs => (false, "GL_EXT_clip_control", self.clip_control_ext),
+        };
+
+        if function.is_null() {
+            return None;
+        }
+        if !core && !unsafe { context.exec_in_context(|| self.has_extension(extension)) } {
+            return None;
+        }
+
+        Some(ClipControl {
+            function: unsafe { std::mem::transmute::<*const c_void, ClipControlFn>(function) },
+        })
+    }
+
+    /// Whether the current context lists `extension`
+    fn has_extension(&self, extension: &str) -> bool {
+        if self.get_integerv.is_null() || self.get_stringi.is_null() {
+            return false;
+        }
+
+        let (get_integerv, get_stringi) = unsafe {
+            (
+                std::mem::transmute::<*const c_void, GetIntegervFn>(self.get_integerv),
+                std::mem::transmute::<*const c_void, GetStringiFn>(self.get_stringi),
+            )
+        };
+
+        let mut count = 0;
+        get_integerv(GL_NUM_EXTENSIONS, &mut count);
+        (0..count.max(0) as u32).any(|index| {
+            let name = get_stringi(GL_EXTENSIONS, index);
+            !name.is_null() && unsafe { CStr::from_ptr(name) }.to_bytes() == extension.as_bytes()
+        })
+    }
+}
+
+/// `glClipControl`, for a context which supports it.
+#[derive(Clone, Copy)]
+pub(crate) struct ClipControl {
+    function: ClipControlFn,
+}
+
+impl ClipControl {
+    /// Set the clip-space depth range `depth_mode` is laid out for: `0` to
+    /// `1` for reverse-Z, or OpenGL's usual `-1` to `1`.
+    ///
+    /// glium doesn't track the clip range, so changing it behind glium's back
+    /// doesn't disturb its state.
+    pub(crate) fn set<F: Facade + ?Sized>(self, facade: &F, depth_mode: DepthMode) {
+        let depth = match depth_mode {
+            DepthMode::Standard => GL_NEGATIVE_ONE_TO_ONE,
+            DepthMode::Reversed => GL_ZERO_TO_ONE,
+        };
+        let function = self.function;
+
+        unsafe {
+            facade
+                .get_context()
+                .exec_in_context(move || function(GL_LOWER_LEFT, depth));
+        }
+    }
+}
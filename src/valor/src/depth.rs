@@ -0,0 +1,42 @@
+use glium::draw_parameters::DepthTest;
+
+/// How depth values are laid out in the depth buffer.
+///
+/// This must match the projection used by the camera, e.g. `Reversed` for a
+/// camera built with `CameraBuilder::with_reverse_z`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DepthMode {
+    /// Near geometry has smaller depth values. Depth is cleared to `1.0` and
+    /// fragments pass when closer than what is already drawn.
+    #[default]
+    Standard,
+    /// Near geometry has larger depth values. Depth is cleared to `0.0` and
+    /// fragments pass when their depth is greater than what is already drawn.
+    ///
+    /// Clip-space depth is switched to run from 0 to 1 with `glClipControl`
+    /// when the context supports it (see `Renderer::has_clip_control`), and
+    /// windows are drawn through an offscreen target with a float depth
+    /// buffer; texture targets and cubemaps always have one. Together these
+    /// keep depth precise out to a far plane at infinity. Without clip
+    /// control, depth still sorts correctly but is no more precise than with
+    /// `Standard`.
+    Reversed,
+}
+
+impl DepthMode {
+    /// The value the depth buffer is cleared to at the start of each frame
+    pub fn clear_value(self) -> f32 {
+        match self {
+            DepthMode::Standard => 1.0,
+            DepthMode::Reversed => 0.0,
+        }
+    }
+
+    /// The comparison which passes fragments nearer to the camera
+    pub fn test(self) -> DepthTest {
+        match self {
+            DepthMode::Standard => DepthTest::IfLess,
+            DepthMode::Reversed => DepthTest::IfMore,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::DepthMode;
+use glium::draw_parameters::{
+    BackfaceCullingMode, Blend, BlendingFunction, Depth, DepthTest, LinearBlendingFactor,
+    PolygonMode,
+};
+
+/// Which faces of triangles are discarded before drawing.
+///
+/// Front faces are those whose vertices wind counter-clockwise on screen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Culling {
+    /// Draw both sides of every triangle
+    #[default]
+    None,
+    /// Discard triangles facing away from the camera
+    Back,
+    /// Discard triangles facing the camera
+    Front,
+}
+
+/// How drawn colors are combined with what is already in the target.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BlendMode {
+    /// Replace the existing color
+    #[default]
+    Opaque,
+    /// Mix with the existing color by the drawn alpha
+    Alpha,
+    /// Mix with the existing color, where the drawn color has already been
+    /// multiplied by its alpha
+    Premultiplied,
+    /// Add to the existing color, e.g. for glows and particles
+    Additive,
+}
+
+/// Fixed-function state used when a material draws a model.
+///
+/// Materials hold a default state, which models may override.
+///
+/// There is no polygon offset for pulling decals or outlines in front of the
+/// surface beneath them, as glium 0.22 has no draw parameter for it.
+///
+/// # Example
+///
+/// ```
+/// # use valor::{BlendMode, Culling, DrawState};
+/// // Transparent glass, which shouldn't hide what is drawn behind it
+/// let glass = DrawState {
+///     depth_write: false,
+///     culling: Culling::Back,
+///     blend: BlendMode::Alpha,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DrawState {
+    /// Whether fragments behind what is already drawn are discarded
+    pub depth_test: bool,
+    /// Whether drawn fragments update the depth buffer
+    pub depth_write: bool,
+    /// Which faces are discarded
+    pub culling: Culling,
+    /// How colors are combined with the target
+    pub blend: BlendMode,
+    /// Draw only the edges of triangles
+    pub wireframe: bool,
+}
+
+impl Default for DrawState {
+    fn default() -> Self {
+        DrawState {
+            depth_test: true,
+            depth_write: true,
+            culling: Culling::None,
+            blend: BlendMode::Opaque,
+            wireframe: false,
+        }
+    }
+}
+
+impl DrawState {
+    /// The glium draw parameters for this state, testing depth as laid out by
+    /// `depth_mode`
+    pub fn parameters<'a>(&self, depth_mode: DepthMode) -> glium::DrawParameters<'a> {
+        let depth = Depth {
+            test: if self.depth_test {
+                depth_mode.test()
+            } else {
+                DepthTest::Overwrite
+            },
+            write: self.depth_write,
+            ..Default::default()
+        };
+
+        let backface_culling = match self.culling {
+            Culling::None => BackfaceCullingMode::CullingDisabled,
+            Culling::Back => BackfaceCullingMode::CullClockwise,
+            Culling::Front => BackfaceCullingMode::CullCounterClockwise,
+        };
+
+        glium::DrawParameters {
+            depth,
+            blend: self.blend.blend(),
+            backface_culling,
+            polygon_mode: if self.wireframe {
+                PolygonMode::Line
+            } else {
+                PolygonMode::Fill
+            },
+            ..Default::default()
+        }
+    }
+}
+
+impl BlendMode {
+    /// The glium blending for this mode
+    fn blend(self) -> Blend {
+        let add = |source, destination| BlendingFunction::Addition {
+            source,
+            destination,
+        };
+
+        match self {
+            BlendMode::Opaque => Blend::default(),
+            BlendMode::Alpha => Blend::alpha_blending(),
+            BlendMode::Premultiplied => Blend {
+                color: add(
+                    LinearBlendingFactor::One,
+                    LinearBlendingFactor::OneMinusSourceAlpha,
+                ),
+                alpha: add(
+                    LinearBlendingFactor::One,
+                    LinearBlendingFactor::OneMinusSourceAlpha,
+                ),
+                constant_value: (0.0, 0.0, 0.0, 0.0),
+            },
+            BlendMode::Additive => Blend {
+                color: add(LinearBlendingFactor::SourceAlpha, LinearBlendingFactor::One),
+                alpha: add(LinearBlendingFactor::Zero, LinearBlendingFactor::One),
+                constant_value: (0.0, 0.0, 0.0, 0.0),
+            },
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+use std::io;
+
+/// Result type returned by fallible operations in valor
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Everything that can go wrong while setting up or drawing with valor.
+#[derive(Debug)]
+pub enum Error {
+    /// No OpenGL context could be created. Holds the reason each attempted
+    /// configuration was rejected, in the order they were tried.
+    Context(Vec<String>),
+    /// A shader failed to compile, with the driver's compile log
+    ShaderCompilation(String),
+    /// Shaders compiled but could not be linked into a program, with the
+    /// driver's link log
+    ShaderLinking(String),
+    /// A program could not be created for another reason, such as missing
+    /// driver support
+    Program(glium::ProgramCreationError),
+    /// A vertex buffer could not be created
+    VertexBuffer(glium::vertex::BufferCreationError),
+    /// A texture could not be created
+    Texture(glium::texture::TextureCreationError),
+    /// A render buffer could not be created
+    RenderBuffer(glium::framebuffer::RenderBufferCreationError),
+    /// A framebuffer could not be assembled from its attachments
+    Framebuffer(glium::framebuffer::ValidationError),
+    /// A texture target was asked for without any color outputs
+    NoColorOutputs,
+    /// A draw call was rejected
+    Draw(glium::DrawError),
+    /// A finished frame could not be presented
+    SwapBuffers(glium::SwapBuffersError),
+    /// Pixels could not be read back from the GPU
+    Read(glium::buffer::ReadError),
+    /// Reading or writing a file failed
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Context(attempts) => {
+                write!(f, "could not create an OpenGL context")?;
+                for attempt in attempts {
+                    write!(f, "\n  - {}", attempt)?;
+                }
+                Ok(())
+            }
+            Error::ShaderCompilation(log) => write!(f, "shader failed to compile:\n{}", log),
+            Error::ShaderLinking(log) => write!(f, "shader program failed to link:\n{}", log),
+            Error::Program(error) => write!(f, "could not create shader program: {}", error),
+            Error::VertexBuffer(error) => write!(f, "could not create vertex buffer: {}", error),
+            Error::Texture(error) => write!(f, "could not create texture: {}", error),
+            Error::RenderBuffer(error) => write!(f, "could not create render buffer: {}", error),
+            Error::Framebuffer(error) => write!(f, "could not create framebuffer: {}", error),
+            Error::NoColorOutputs => write!(f, "a texture target needs a color output"),
+            Error::Draw(error) => write!(f, "draw failed: {}", error),
+            Error::SwapBuffers(error) => write!(f, "could not present frame: {}", error),
+            Error::Read(error) => write!(f, "could not read pixels: {}", error),
+            Error::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Context(_)
+            | Error::ShaderCompilation(_)
+            | Error::ShaderLinking(_)
+            | Error::NoColorOutputs => None,
+            Error::Program(error) => Some(error),
+            Error::VertexBuffer(error) => Some(error),
+            Error::Texture(error) => Some(error),
+            Error::RenderBuffer(error) => Some(error),
+            Error::Framebuffer(error) => Some(error),
+            Error::Draw(error) => Some(error),
+            Error::SwapBuffers(error) => Some(error),
+            Error::Read(error) => Some(error),
+            Error::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<glium::ProgramCreationError> for Error {
+    fn from(error: glium::ProgramCreationError) -> Self {
+        match error {
+            glium::ProgramCreationError::CompilationError(log) => Error::ShaderCompilation(log),
+            glium::ProgramCreationError::LinkingError(log) => Error::ShaderLinking(log),
+            error => Error::Program(error),
+        }
+    }
+}
+
+/// Implement `From` for errors which are wrapped as they are
+macro_rules! wrap {
+    ($($source:ty => $variant:ident,)*) => {
+        $(
+            impl From<$source> for Error {
+                fn from(error: $source) -> Self {
+                    Error::$variant(error)
+                }
+            }
+        )*
+    };
+}
+
+wrap! {
+    glium::vertex::BufferCreationError => VertexBuffer,
+    glium::texture::TextureCreationError => Texture,
+    glium::framebuffer::RenderBufferCreationError => RenderBuffer,
+    glium::framebuffer::ValidationError => Framebuffer,
+    glium::DrawError => Draw,
+    glium::SwapBuffersError => SwapBuffers,
+    glium::buffer::ReadError => Read,
+    io::Error => Io,
+}
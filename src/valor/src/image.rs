@@ -0,0 +1,126 @@
+use glium::texture::Texture2dDataSink;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+/// An 8-bit RGBA image, such as a frame read back from the `Renderer`.
+///
+/// Pixels are stored row by row starting from the top left, the way image
+/// files are laid out, rather than from the bottom left as OpenGL does.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// RGBA bytes, four per pixel
+    pub data: Vec<u8>,
+}
+
+impl Image {
+    /// Create an image from RGBA bytes laid out from the top left, or `None`
+    /// if there aren't exactly four bytes for each pixel
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        if byte_len(width, height)? != data.len() {
+            return None;
+        }
+
+        Some(Image {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// The RGBA value of the pixel `x` across and `y` down from the top left,
+    /// or `None` if that's outside the image
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.data[start..start + 4]);
+        Some(pixel)
+    }
+
+    /// Read a PNG file. Images without an alpha channel are made opaque.
+    pub fn load_png<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => expand(&buffer, 3, |rgb| [rgb[0], rgb[1], rgb[2], 255]),
+            png::ColorType::GrayscaleAlpha => expand(&buffer, 2, |ga| [ga[0], ga[0], ga[0], ga[1]]),
+            png::ColorType::Grayscale => expand(&buffer, 1, |g| [g[0], g[0], g[0], 255]),
+            png::ColorType::Indexed => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "indexed PNG was not expanded",
+                ))
+            }
+        };
+
+        Image::new(info.width, info.height, data).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "PNG data doesn't match its dimensions",
+            )
+        })
+    }
+
+    /// Write the image to a PNG file
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+
+        Ok(())
+    }
+}
+
+impl Texture2dDataSink<(u8, u8, u8, u8)> for Image {
+    fn from_raw(data: Cow<[(u8, u8, u8, u8)]>, width: u32, height: u32) -> Self {
+        // OpenGL returns the bottom row first
+        let mut bytes = Vec::with_capacity(data.len() * 4);
+        for row in data.chunks(width.max(1) as usize).rev() {
+            for &(r, g, b, a) in row {
+                bytes.extend_from_slice(&[r, g, b, a]);
+            }
+        }
+
+        Image::new(width, height, bytes).expect("OpenGL returned a pixel for every position")
+    }
+}
+
+/// The number of RGBA bytes in an image, or `None` if it can't be addressed
+fn byte_len(width: u32, height: u32) -> Option<usize> {
+    (width as usize)
+        .checked_mul(height as usize)?
+        .checked_mul(4)
+}
+
+/// Convert pixels `channels` bytes wide to RGBA
+fn expand<F>(buffer: &[u8], channels: usize, convert: F) -> Vec<u8>
+where
+    F: Fn(&[u8]) -> [u8; 4],
+{
+    let mut data = Vec::with_capacity(buffer.len() / channels * 4);
+    for pixel in buffer.chunks(channels) {
+        data.extend_from_slice(&convert(pixel));
+    }
+    data
+}
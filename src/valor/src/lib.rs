@@ -6,21 +6,23 @@
 //! Using the `ValorBuilder` struct is an easy way to create a `Renderer` and
 //! window with common options for games.
 //!
-//! ```
+//! ```no_run
 //! # extern crate valor;
 //! # use valor::ValorBuilder;
 //! # pub fn main() {
 //! let (mut events_loop, mut renderer) = ValorBuilder::new()
 //!     .with_title(&"Minimal")
-//!     .with_dimensions(800, 600)
-//!     .finish();
+//!     .with_dimensions(800.0, 600.0)
+//!     .finish()
+//!     .unwrap();
 //! # }
 //! ```
 //!
 //! After you have a `Renderer`, there's some other things you can try:
 //!
 //!  - Draw some things using a `Material` and `Model`. One is provided by the
-//!  `simple` module, but you can make your own.
+//!    `simple` module, but you can make your own.
+//!  - Submit draws to a `RenderQueue` so they're drawn in an efficient order
 //!  - Create a `Camera` and use it to move your view around
 //!  - Create a `Scene` and traverse it when rendering
 
@@ -34,15 +36,31 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 mod builder;
+mod capture;
+mod clip;
+mod depth;
+mod draw_state;
+mod error;
+mod image;
 mod material;
+mod queue;
 mod renderer;
 pub mod simple;
+mod target;
 mod text;
 
 pub use crate::builder::ValorBuilder;
-pub use crate::material::Material;
+pub use crate::capture::FrameRecorder;
+pub use crate::depth::DepthMode;
+pub use crate::draw_state::{BlendMode, Culling, DrawState};
+pub use crate::error::{Error, Result};
+pub use crate::image::Image;
+pub use crate::material::{Material, MaterialKey};
+pub use crate::queue::{QueueStats, RenderQueue};
 pub use crate::renderer::Renderer;
+pub use crate::target::{RenderTarget, TextureTarget};
 pub use crate::text::{Text, TextHandle};
+pub use valor_camera::Viewport;
 
 /// Utility type used when passing around models
 pub type Handle<T> = Rc<RefCell<Box<T>>>;
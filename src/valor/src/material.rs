@@ -1,5 +1,4 @@
-use crate::Handle;
-use glium;
+use crate::{DrawState, Handle, RenderTarget, Result, Viewport};
 
 /// A material performs the drawing operation for a model.
 ///
@@ -9,11 +8,66 @@ pub trait Material<T> {
     ///
     /// Currently this trait assumes specific knowledge of data required to
     /// perform the draw, but that will go away in future versions.
+    ///
+    /// When a `viewport` is given, drawing is mapped into and clipped to that
+    /// rectangle of the target rather than covering the whole of it;
+    /// `RenderTarget::viewport_rect` converts it for glium.
+    ///
+    /// `target` may be the window or a texture; see `Renderer::render` and
+    /// `Renderer::render_to`. Depth should be tested as laid out by
+    /// `target.depth_mode()`.
     fn draw(
         &self,
-        target: &mut glium::Frame,
+        target: &mut RenderTarget,
         model: Handle<T>,
         u_view_proj: [[f32; 4]; 4],
         u_world: [[f32; 4]; 4],
-    );
+        viewport: Option<&Viewport>,
+    ) -> Result<()>;
+
+    /// Whether drawing `model` blends with what is already in the target,
+    /// so it has to be drawn after opaque models. `RenderQueue` uses this to
+    /// order draws.
+    fn is_transparent(&self, _model: &T) -> bool {
+        false
+    }
+
+    /// The program and state `draw` binds for `model`. `RenderQueue` draws
+    /// models with equal keys one after another, so only their buffers and
+    /// uniforms change between draws.
+    ///
+    /// By default draws are grouped by material alone. Materials sharing a
+    /// program with others, or whose models override their state, should
+    /// return `MaterialKey::new` with the program and the model's state.
+    fn sort_key(&self, _model: &T) -> MaterialKey {
+        MaterialKey {
+            binding: Binding::Material(self as *const Self as *const () as usize),
+        }
+    }
+}
+
+/// Identifies the GPU state a draw needs bound, so draws needing the same
+/// state can be grouped. See `Material::sort_key`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaterialKey {
+    binding: Binding,
+}
+
+/// What a `MaterialKey` was made from, kept apart so a material's address
+/// can never be mistaken for a program's
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Binding {
+    /// Whatever the material at this address binds
+    Material(usize),
+    /// The program at this address in a state
+    Program(usize, DrawState),
+}
+
+impl MaterialKey {
+    /// A key for drawing with `program` in `state`
+    pub fn new(program: &glium::Program, state: DrawState) -> Self {
+        MaterialKey {
+            binding: Binding::Program(program as *const glium::Program as usize, state),
+        }
+    }
 }
@@ -0,0 +1,311 @@
+use crate::{Handle, Material, MaterialKey, RenderTarget, Result, Viewport};
+
+/// Draws collected while walking a scene, flushed together in an order which
+/// draws correctly and cheaply.
+///
+/// Opaque draws are batched by `Material::sort_key`, so draws sharing a
+/// program and state run one after another and glium binds that program and
+/// state once per batch rather than once per draw. Within a batch they are
+/// drawn front to back so hidden fragments fail the depth test early.
+/// Transparent draws come after every opaque one, back to front so they blend
+/// over what is behind them, batched only where depths tie.
+///
+/// The queue is emptied by `flush`, keeping its storage, so one queue can be
+/// reused every frame.
+///
+/// # Example
+///
+/// ```no_run
+/// # use valor::simple::{Material as SimpleMaterial, Model};
+/// # use valor::{RenderQueue, ValorBuilder};
+/// # let (_, mut renderer) = ValorBuilder::new().finish()?;
+/// # let view_proj = [[0.0; 4]; 4];
+/// # let world = [[0.0; 4]; 4];
+/// let material = SimpleMaterial::new(renderer.facade())?;
+/// let model = Model::new(&mut renderer, &[])?;
+/// let mut queue = RenderQueue::new();
+///
+/// renderer.render(|target| {
+///     // While walking the scene
+///     queue.submit(&material, model.clone(), world, 2.0);
+///
+///     let stats = queue.flush(target, view_proj, None)?;
+///     println!("{} draws in {} batches", stats.draws, stats.batches);
+///     Ok(())
+/// })?;
+/// # Ok::<(), valor::Error>(())
+/// ```
+pub struct RenderQueue<'a, T> {
+    opaque: Vec<Item<'a, T>>,
+    transparent: Vec<Item<'a, T>>,
+    stats: QueueStats,
+}
+
+/// Counts from the last time a `RenderQueue` was flushed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Opaque models drawn
+    pub opaque: usize,
+    /// Transparent models drawn
+    pub transparent: usize,
+    /// Models drawn in total
+    pub draws: usize,
+    /// Runs of consecutive draws sharing a program and state, each of which
+    /// binds them once
+    pub batches: usize,
+}
+
+/// A submitted draw
+struct Item<'a, T> {
+    material: &'a dyn Material<T>,
+    key: MaterialKey,
+    model: Handle<T>,
+    world: [[f32; 4]; 4],
+    depth: f32,
+}
+
+impl<'a, T> Default for RenderQueue<'a, T> {
+    fn default() -> Self {
+        RenderQueue {
+            opaque: Vec::new(),
+            transparent: Vec::new(),
+            stats: QueueStats::default(),
+        }
+    }
+}
+
+impl<'a, T> RenderQueue<'a, T> {
+    /// Create an empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `model` to be drawn by `material` with the `world` transform.
+    ///
+    /// `depth` is the model's distance from the camera, or any value which
+    /// increases away from it, such as view-space depth.
+    pub fn submit(
+        &mut self,
+        material: &'a dyn Material<T>,
+        model: Handle<T>,
+        world: [[f32; 4]; 4],
+        depth: f32,
+    ) {
+        let (transparent, key) = {
+            let model = model.borrow();
+            (material.is_transparent(&model), material.sort_key(&model))
+        };
+        let item = Item {
+            material,
+            key,
+            model,
+            world,
+            depth,
+        };
+
+        if transparent {
+            self.transparent.push(item);
+        } else {
+            self.opaque.push(item);
+        }
+    }
+
+    /// The number of draws waiting to be flushed
+    pub fn len(&self) -> usize {
+        self.opaque.len() + self.transparent.len()
+    }
+
+    /// Whether there are no draws waiting to be flushed
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sort and draw everything submitted since the last flush into
+    /// `target`, leaving the queue empty.
+    ///
+    /// Stops at the first draw which fails, discarding the rest.
+    pub fn flush(
+        &mut self,
+        target: &mut RenderTarget,
+        u_view_proj: [[f32; 4]; 4],
+        viewport: Option<&Viewport>,
+    ) -> Result<QueueStats> {
+        self.drain(|item| {
+            item.material
+                .draw(target, item.model, u_view_proj, item.world, viewport)
+        })
+    }
+
+    /// Sort the queued items and pass each to `draw` in turn, counting them
+    /// as `flush` does
+    fn drain<F>(&mut self, mut draw: F) -> Result<QueueStats>
+    where
+        F: FnMut(Item<'a, T>) -> Result<()>,
+    {
+        self.opaque
+            .sort_by(|a, b| a.key.cmp(&b.key).then(a.depth.total_cmp(&b.depth)));
+        self.transparent
+            .sort_by(|a, b| b.depth.total_cmp(&a.depth).then(a.key.cmp(&b.key)));
+
+        let mut stats = QueueStats {
+            opaque: self.opaque.len(),
+            transparent: self.transparent.len(),
+            ..QueueStats::default()
+        };
+
+        let mut previous = None;
+        for item in self.opaque.drain(..).chain(self.transparent.drain(..)) {
+            if previous != Some(item.key) {
+                stats.batches += 1;
+                previous = Some(item.key);
+            }
+
+            draw(item)?;
+            stats.draws += 1;
+        }
+
+        self.stats = stats;
+        Ok(stats)
+    }
+
+    /// Counts from the last successful flush
+    pub fn stats(&self) -> QueueStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A model which only carries a name to recognize it by
+    struct Model {
+        name: &'static str,
+        transparent: bool,
+    }
+
+    /// A material which is never asked to draw, since the tests drain the
+    /// queue themselves. Each instance has its own default sort key, so it
+    /// mustn't be zero-sized, which could give two instances one address.
+    struct TestMaterial {
+        _id: u8,
+    }
+
+    impl Material<Model> for TestMaterial {
+        fn draw(
+            &self,
+            _target: &mut RenderTarget,
+            _model: Handle<Model>,
+            _u_view_proj: [[f32; 4]; 4],
+            _u_world: [[f32; 4]; 4],
+            _viewport: Option<&Viewport>,
+        ) -> Result<()> {
+            unreachable!("tests drain the queue without a render target")
+        }
+
+        fn is_transparent(&self, model: &Model) -> bool {
+            model.transparent
+        }
+    }
+
+    fn submit<'a>(
+        queue: &mut RenderQueue<'a, Model>,
+        material: &'a TestMaterial,
+        name: &'static str,
+        transparent: bool,
+        depth: f32,
+    ) {
+        let model = Rc::new(RefCell::new(Box::new(Model { name, transparent })));
+        queue.submit(material, model, [[0.0; 4]; 4], depth);
+    }
+
+    /// Drain `queue`, returning the names of the models in the order they
+    /// would have been drawn, along with the stats
+    fn record(queue: &mut RenderQueue<Model>) -> (Vec<&'static str>, QueueStats) {
+        let mut drawn = Vec::new();
+        let stats = queue
+            .drain(|item| {
+                drawn.push(item.model.borrow().name);
+                Ok(())
+            })
+            .unwrap();
+        (drawn, stats)
+    }
+
+    #[test]
+    fn opaque_draws_group_by_key_then_run_front_to_back() {
+        let (first, second) = (TestMaterial { _id: 1 }, TestMaterial { _id: 2 });
+        let mut queue = RenderQueue::new();
+        submit(&mut queue, &first, "first far", false, 9.0);
+        submit(&mut queue, &second, "second near", false, 1.0);
+        submit(&mut queue, &first, "first near", false, 2.0);
+        submit(&mut queue, &second, "second far", false, 8.0);
+
+        // Keys order by address, so either material's batch may come first
+        let (drawn, stats) = record(&mut queue);
+        let first_batch = ["first near", "first far"];
+        let second_batch = ["second near", "second far"];
+        assert!(
+            drawn == [first_batch, second_batch].concat()
+                || drawn == [second_batch, first_batch].concat(),
+            "draws should be grouped by key, then near to far, not {:?}",
+            drawn
+        );
+        assert_eq!(stats.batches, 2);
+    }
+
+    #[test]
+    fn transparent_draws_run_back_to_front_after_opaque_ones() {
+        let (first, second) = (TestMaterial { _id: 1 }, TestMaterial { _id: 2 });
+        let mut queue = RenderQueue::new();
+        submit(&mut queue, &first, "near glass", true, 1.0);
+        submit(&mut queue, &second, "far glass", true, 9.0);
+        submit(&mut queue, &first, "wall", false, 5.0);
+        submit(&mut queue, &second, "middle glass", true, 4.0);
+
+        let (drawn, stats) = record(&mut queue);
+        assert_eq!(drawn, ["wall", "far glass", "middle glass", "near glass"]);
+        assert_eq!(stats.opaque, 1);
+        assert_eq!(stats.transparent, 3);
+        assert_eq!(stats.draws, 4);
+    }
+
+    #[test]
+    fn batches_count_runs_of_equal_keys() {
+        let (first, second) = (TestMaterial { _id: 1 }, TestMaterial { _id: 2 });
+        let mut queue = RenderQueue::new();
+        for &depth in [1.0, 2.0, 3.0].iter() {
+            submit(&mut queue, &first, "first", false, depth);
+            submit(&mut queue, &second, "second", false, depth);
+        }
+        let (_, stats) = record(&mut queue);
+        assert_eq!(stats.batches, 2, "opaque draws should share two batches");
+
+        // Interleaved transparent draws can't be batched, since depth comes
+        // first, but neighbours with the same key still share one
+        submit(&mut queue, &first, "a", true, 4.0);
+        submit(&mut queue, &second, "b", true, 3.0);
+        submit(&mut queue, &first, "c", true, 2.0);
+        submit(&mut queue, &first, "d", true, 1.0);
+        let (_, stats) = record(&mut queue);
+        assert_eq!(stats.batches, 3);
+        assert_eq!(queue.stats(), stats);
+    }
+
+    #[test]
+    fn draining_empties_the_queue() {
+        let material = TestMaterial { _id: 1 };
+        let mut queue = RenderQueue::new();
+        submit(&mut queue, &material, "opaque", false, 1.0);
+        submit(&mut queue, &material, "transparent", true, 1.0);
+        assert_eq!(queue.len(), 2);
+
+        record(&mut queue);
+        assert!(queue.is_empty());
+        let (drawn, stats) = record(&mut queue);
+        assert!(drawn.is_empty());
+        assert_eq!(stats, QueueStats::default());
+    }
+}
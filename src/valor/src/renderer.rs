@@ -1,17 +1,65 @@
-use glium;
+use crate::capture::{CaptureCallback, PendingCapture};
+use crate::clip::ClipControl;
+use crate::target::TargetSurface;
+use crate::{DepthMode, Image, RenderTarget, Result, TextureTarget, Viewport};
+use glium::backend::Facade;
+use glium::framebuffer::{DepthRenderBuffer, SimpleFrameBuffer};
+use glium::glutin;
+use glium::texture::pixel_buffer::PixelBuffer;
+use glium::texture::{DepthFormat, Texture2d};
+use glium::uniforms::MagnifySamplerFilter;
+use glium::Surface;
+use std::collections::VecDeque;
+use std::path::Path;
+
+/// Number of frames to leave a capture in flight before reading it back, so
+/// the GPU has finished the copy and reading it doesn't stall
+const CAPTURE_LATENCY: u64 = 2;
+
+/// The OpenGL context a `Renderer` draws with
+enum Backend {
+    /// A context attached to a window, drawing into its back buffer
+    Window(glium::Display),
+    /// A context without a window, drawing into offscreen buffers
+    Headless {
+        context: glium::HeadlessRenderer,
+        target: TextureTarget,
+    },
+}
 
 /// Interface responsible for window creation and initiating drawing.
 pub struct Renderer {
-    /// The underlying `glium::Display` we abstract over.
-    pub display: glium::Display,
+    /// The underlying glium context we abstract over.
+    backend: Backend,
 
     /// Color for OpenGL clear command.
     clear_color: [f32; 4],
 
-    /// Width of the frame.
+    /// Layout of the depth buffer, deciding its clear value and comparison.
+    depth_mode: DepthMode,
+    /// `glClipControl`, if the context supports choosing the clip-space depth
+    /// range.
+    clip_control: Option<ClipControl>,
+    /// Float depth target a window draws into for reverse-Z, since the
+    /// window's own depth buffer holds integers.
+    offscreen: Option<TextureTarget>,
+
+    /// Width of the frame in physical pixels.
     pub width: u32,
-    /// Height of the frame.
+    /// Height of the frame in physical pixels.
     pub height: u32,
+    /// Ratio of physical pixels to logical pixels on the window's monitor.
+    pub dpi_factor: f64,
+
+    /// Callbacks to run with the new frame size when it changes.
+    resize_listeners: Vec<Box<dyn FnMut(u32, u32)>>,
+
+    /// Number of frames drawn so far.
+    frame: u64,
+    /// Callbacks waiting for the next frame to be captured.
+    capture_requests: Vec<CaptureCallback>,
+    /// Captured frames still being copied off the GPU, oldest first.
+    pending_captures: VecDeque<PendingCapture>,
 }
 
 impl Renderer {
@@ -19,45 +67,469 @@ impl Renderer {
     /// instead of this method.
     pub(crate) fn new(
         display: glium::Display,
+        clip_control: Option<ClipControl>,
         clear_color: [f32; 4],
+        depth_mode: DepthMode,
         width: u32,
         height: u32,
+        dpi_factor: f64,
     ) -> Self {
+        if let Some(clip_control) = clip_control {
+            clip_control.set(&display, depth_mode);
+        }
+
         Renderer {
-            display,
+            backend: Backend::Window(display),
             clear_color,
+            depth_mode,
+            clip_control,
+            offscreen: None,
             width,
             height,
+            dpi_factor,
+            resize_listeners: Vec::new(),
+            frame: 0,
+            capture_requests: Vec::new(),
+            pending_captures: VecDeque::new(),
         }
     }
 
+    /// Create a rendering instance without a window, drawing each frame into
+    /// an offscreen framebuffer of `width` by `height` pixels. You should
+    /// probably use `ValorBuilder::finish_headless` instead of this method.
+    pub(crate) fn new_headless(
+        context: glium::HeadlessRenderer,
+        clip_control: Option<ClipControl>,
+        clear_color: [f32; 4],
+        depth_mode: DepthMode,
+        width: u32,
+        height: u32,
+    ) -> Result<Self> {
+        let target = TextureTarget::new(&context, width, height)?;
+        if let Some(clip_control) = clip_control {
+            clip_control.set(&context, depth_mode);
+        }
+
+        Ok(Renderer {
+            backend: Backend::Headless { context, target },
+            clear_color,
+            depth_mode,
+            clip_control,
+            offscreen: None,
+            width,
+            height,
+            dpi_factor: 1.0,
+            resize_listeners: Vec::new(),
+            frame: 0,
+            capture_requests: Vec::new(),
+            pending_captures: VecDeque::new(),
+        })
+    }
+
+    /// The window's `glium::Display`, or `None` for a headless renderer.
+    pub fn display(&self) -> Option<&glium::Display> {
+        match self.backend {
+            Backend::Window(ref display) => Some(display),
+            Backend::Headless { .. } => None,
+        }
+    }
+
+    /// The OpenGL context, for creating buffers, textures and programs.
+    pub fn facade(&self) -> &dyn Facade {
+        match self.backend {
+            Backend::Window(ref display) => display,
+            Backend::Headless { ref context, .. } => context,
+        }
+    }
+
+    /// Whether the renderer draws offscreen rather than into a window.
+    pub fn is_headless(&self) -> bool {
+        self.display().is_none()
+    }
+
     /// Setter for the frame dimensions, as these can change over the course of
-    /// a window's lifetime.
+    /// a window's lifetime. Resize listeners are notified if the size changed.
+    ///
+    /// Sizes with no area, as reported while a window is minimized, are
+    /// ignored, so listeners never have to divide by a zero height.
     pub fn set_dimensions(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 || (width, height) == (self.width, self.height) {
+            return;
+        }
+
         self.width = width;
         self.height = height;
+
+        for listener in &mut self.resize_listeners {
+            listener(width, height);
+        }
+    }
+
+    /// Register a callback to run with the new frame width and height
+    /// whenever the frame is resized, e.g. to keep a camera's aspect ratio
+    /// matching the window.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// # use valor::ValorBuilder;
+    /// # use valor_camera::CameraBuilder;
+    /// # let (_, mut renderer) = ValorBuilder::new().finish().unwrap();
+    /// let camera = Rc::new(RefCell::new(CameraBuilder::new().finish()));
+    ///
+    /// let resized = camera.clone();
+    /// renderer.on_resize(move |width, height| {
+    ///     resized
+    ///         .borrow_mut()
+    ///         .set_aspect_ratio(width as f32 / height as f32);
+    /// });
+    /// ```
+    pub fn on_resize<F>(&mut self, listener: F)
+    where
+        F: FnMut(u32, u32) + 'static,
+    {
+        self.resize_listeners.push(Box::new(listener));
+    }
+
+    /// Update the renderer from a window event. Resizes and DPI changes are
+    /// applied to the drawing surface and passed on to resize listeners.
+    ///
+    /// Returns `true` if the event was one the renderer handles.
+    pub fn handle_event(&mut self, event: &glutin::Event) -> bool {
+        let event = match event {
+            glutin::Event::WindowEvent { event, .. } => event,
+            _ => return false,
+        };
+
+        match *event {
+            glutin::WindowEvent::Resized(size) => {
+                self.resize(size.to_physical(self.dpi_factor));
+                true
+            }
+            glutin::WindowEvent::HiDpiFactorChanged(dpi_factor) => {
+                let size = self
+                    .display()
+                    .and_then(|display| display.gl_window().get_inner_size());
+                self.dpi_factor = dpi_factor;
+                if let Some(size) = size {
+                    self.resize(size.to_physical(dpi_factor));
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Resize the drawing surface and record the new dimensions. Minimized
+    /// windows keep their last drawable size.
+    fn resize(&mut self, size: glutin::dpi::PhysicalSize) {
+        use glium::glutin::GlContext;
+
+        let (width, height): (u32, u32) = size.into();
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        if let Some(display) = self.display() {
+            display.gl_window().resize(size);
+        }
+
+        self.set_dimensions(width, height);
+    }
+
+    /// The depth layout used when clearing each frame, and carried by the
+    /// targets handed to materials.
+    pub fn depth_mode(&self) -> DepthMode {
+        self.depth_mode
+    }
+
+    /// Change the depth layout, e.g. when switching to a camera which uses a
+    /// reverse-Z projection.
+    pub fn set_depth_mode(&mut self, depth_mode: DepthMode) {
+        self.depth_mode = depth_mode;
+        if let Some(clip_control) = self.clip_control {
+            clip_control.set(self.facade(), depth_mode);
+        }
+    }
+
+    /// Whether the context can switch clip-space depth to run from 0 to 1,
+    /// which `DepthMode::Reversed` needs to improve depth precision. It needs
+    /// OpenGL 4.5, ARB_clip_control or EXT_clip_control.
+    pub fn has_clip_control(&self) -> bool {
+        self.clip_control.is_some()
+    }
+
+    /// Keep the window's offscreen target in step with the depth mode and
+    /// window size. Reverse-Z needs a float depth buffer, which a window
+    /// can't be created with, so its frames are drawn offscreen and copied
+    /// into the window.
+    fn prepare_offscreen(&mut self) -> Result<()> {
+        let display = match self.backend {
+            Backend::Window(ref display) => display,
+            Backend::Headless { .. } => return Ok(()),
+        };
+
+        if self.depth_mode == DepthMode::Standard {
+            self.offscreen = None;
+        } else if self.offscreen.as_ref().map(TextureTarget::dimensions)
+            != Some((self.width, self.height))
+        {
+            self.offscreen = Some(TextureTarget::new(display, self.width, self.height)?);
+        }
+
+        Ok(())
+    }
+
+    /// Copy a frame drawn offscreen into the window and present it
+    fn present_offscreen(&self) -> Result<()> {
+        if let (Backend::Window(ref display), Some(ref offscreen)) =
+            (&self.backend, &self.offscreen)
+        {
+            let frame = display.draw();
+            let (width, height) = frame.get_dimensions();
+            offscreen.color().as_surface().blit_whole_color_to(
+                &frame,
+                &glium::BlitTarget {
+                    left: 0,
+                    bottom: 0,
+                    width: width as i32,
+                    height: height as i32,
+                },
+                MagnifySamplerFilter::Nearest,
+            );
+            frame.finish()?;
+        }
+
+        Ok(())
     }
 
     /// Initiate a frame draw. This will begin the frame and call `callback`
     /// when the frame is ready to be drawn to.
-    pub fn render<F>(&mut self, callback: F)
+    ///
+    /// Windowed renderers draw into the window, and headless renderers into
+    /// their offscreen framebuffer, which `read_pixels` reads back. With
+    /// `DepthMode::Reversed`, windowed renderers draw offscreen too, for a
+    /// float depth buffer, and copy the frame into the window afterwards.
+    ///
+    /// The frame is still presented if `callback` fails, and its error is
+    /// returned.
+    pub fn render<F>(&mut self, callback: F) -> Result<()>
     where
-        F: Fn(&mut glium::Frame),
+        F: FnOnce(&mut RenderTarget) -> Result<()>,
     {
-        use glium::Surface;
+        self.resolve_captures(false)?;
+        self.prepare_offscreen()?;
 
-        let mut target = self.display.draw();
+        let mut target = match self.backend {
+            Backend::Window(ref display) => match self.offscreen {
+                Some(ref offscreen) => offscreen.target(display, self.depth_mode)?,
+                None => RenderTarget::new(TargetSurface::Frame(display.draw()), self.depth_mode),
+            },
+            Backend::Headless {
+                ref context,
+                ref target,
+            } => target.target(context, self.depth_mode)?,
+        };
 
-        // Clear background color
-        target.clear_color(
-            self.clear_color[0],
-            self.clear_color[1],
-            self.clear_color[2],
-            self.clear_color[3],
+        self.clear(&mut target);
+
+        let mut result = callback(&mut target);
+
+        if result.is_ok() && !self.capture_requests.is_empty() {
+            match self.copy_pixels(&target) {
+                Ok(pixels) => self.pending_captures.push_back(PendingCapture {
+                    pixels,
+                    frame: self.frame,
+                    callbacks: std::mem::take(&mut self.capture_requests),
+                }),
+                Err(error) => result = Err(error),
+            }
+        }
+
+        let presented = target.finish().and_then(|()| self.present_offscreen());
+
+        self.frame += 1;
+
+        result.and(presented)
+    }
+
+    /// Start copying the contents of `target` into a pixel buffer, without
+    /// waiting for the copy to finish
+    fn copy_pixels<S: glium::Surface>(&self, target: &S) -> Result<PixelBuffer<(u8, u8, u8, u8)>> {
+        let (width, height) = target.get_dimensions();
+        let texture = Texture2d::empty(self.facade(), width, height)?;
+
+        target.blit_whole_color_to(
+            &texture.as_surface(),
+            &glium::BlitTarget {
+                left: 0,
+                bottom: 0,
+                width: width as i32,
+                height: height as i32,
+            },
+            MagnifySamplerFilter::Nearest,
         );
 
-        callback(&mut target);
+        Ok(texture.read_to_pixel_buffer())
+    }
+
+    /// Hand captured frames which have finished copying to their callbacks.
+    /// With `wait`, every capture is read back, waiting on the GPU if needed.
+    fn resolve_captures(&mut self, wait: bool) -> Result<()> {
+        while let Some(capture) = self.pending_captures.pop_front() {
+            if !wait && capture.frame + CAPTURE_LATENCY > self.frame {
+                self.pending_captures.push_front(capture);
+                break;
+            }
+
+            capture.resolve()?;
+        }
+
+        Ok(())
+    }
+
+    /// Capture the next frame drawn, passing it to `callback` once it has been
+    /// read back. Reading back happens a couple of frames later through a
+    /// pixel buffer, so taking a screenshot doesn't stall drawing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use valor::ValorBuilder;
+    /// # let (_, mut renderer) = ValorBuilder::new().finish().unwrap();
+    /// renderer.capture_next_frame(|image| {
+    ///     image.save_png("screenshot.png").unwrap();
+    /// });
+    /// ```
+    pub fn capture_next_frame<F>(&mut self, callback: F)
+    where
+        F: FnOnce(Image) + 'static,
+    {
+        self.capture_requests.push(Box::new(callback));
+    }
+
+    /// Read back every captured frame still in flight, waiting for the GPU if
+    /// needed, e.g. before exiting.
+    pub fn finish_captures(&mut self) -> Result<()> {
+        self.resolve_captures(true)
+    }
+
+    /// Clear a target's background color and depth
+    fn clear<S: glium::Surface>(&self, target: &mut S) {
+        target.clear_color_and_depth(
+            (
+                self.clear_color[0],
+                self.clear_color[1],
+                self.clear_color[2],
+                self.clear_color[3],
+            ),
+            self.depth_mode.clear_value(),
+        );
+    }
+
+    /// Read back the last frame drawn, e.g. to check the output of a headless
+    /// renderer.
+    pub fn read_pixels(&self) -> Image {
+        match self.backend {
+            Backend::Window(ref display) => display.read_front_buffer(),
+            Backend::Headless { ref target, .. } => target.color().read(),
+        }
+    }
+
+    /// Write the last frame drawn to a PNG file.
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        Ok(self.read_pixels().save_png(path)?)
+    }
+
+    /// Draw into the textures of `target` rather than the window, e.g. for an
+    /// in-world monitor or a post-processing pass. The textures are cleared
+    /// before `callback` is called to draw into them.
+    pub fn render_to<F>(&self, target: &TextureTarget, callback: F) -> Result<()>
+    where
+        F: FnOnce(&mut RenderTarget) -> Result<()>,
+    {
+        let mut target = target.target(self.facade(), self.depth_mode)?;
+        self.clear(&mut target);
+        callback(&mut target)
+    }
+
+    /// Initiate a frame draw split into several viewports, e.g. one per
+    /// player. `callback` is called once per viewport with its index, and
+    /// should draw with that viewport so nothing spills into its neighbours.
+    ///
+    /// See `Viewport::split` for laying out the viewports.
+    pub fn render_viewports<F>(&mut self, viewports: &[Viewport], mut callback: F) -> Result<()>
+    where
+        F: FnMut(&mut RenderTarget, usize, &Viewport) -> Result<()>,
+    {
+        self.render(|target| {
+            for (index, viewport) in viewports.iter().enumerate() {
+                callback(target, index, viewport)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Draw into each face of `cubemap`, e.g. to capture an environment map or
+    /// reflection probe from the scene. `callback` is called once per face
+    /// with the face's layer index, in the order `+X, -X, +Y, -Y, +Z, -Z`,
+    /// and should draw the scene as seen from that face.
+    ///
+    /// Each face is cleared to the clear color before drawing, and a float
+    /// depth buffer matching the cubemap's size is provided.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use valor::ValorBuilder;
+    /// # use valor_camera::{CubeCamera, CubeFace};
+    /// # use cgmath::Vector3;
+    /// # let (_, renderer) = ValorBuilder::new().finish()?;
+    /// let cubemap = glium::texture::Cubemap::empty(renderer.facade(), 256)?;
+    /// let probe = CubeCamera::new(Vector3::new(0.0, 1.0, 0.0));
+    ///
+    /// renderer.render_cubemap(&cubemap, |target, face| {
+    ///     let view_proj: [[f32; 4]; 4] = probe.view_proj(CubeFace::ALL[face]).into();
+    ///     // Draw the scene into `target` with `view_proj`
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), valor::Error>(())
+    /// ```
+    pub fn render_cubemap<F>(
+        &self,
+        cubemap: &glium::texture::Cubemap,
+        mut callback: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&mut RenderTarget, usize) -> Result<()>,
+    {
+        use glium::texture::CubeLayer;
+
+        const LAYERS: [CubeLayer; 6] = [
+            CubeLayer::PositiveX,
+            CubeLayer::NegativeX,
+            CubeLayer::PositiveY,
+            CubeLayer::NegativeY,
+            CubeLayer::PositiveZ,
+            CubeLayer::NegativeZ,
+        ];
+
+        let size = cubemap.get_width();
+        let depth = DepthRenderBuffer::new(self.facade(), DepthFormat::F32, size, size)?;
+
+        for layer in LAYERS.iter() {
+            let face = cubemap.main_level().image(*layer);
+            let framebuffer = SimpleFrameBuffer::with_depth_buffer(self.facade(), face, &depth)?;
+            let mut target =
+                RenderTarget::new(TargetSurface::Framebuffer(framebuffer), self.depth_mode);
+
+            self.clear(&mut target);
+
+            callback(&mut target, layer.get_layer_index())?;
+        }
 
-        target.finish().unwrap();
+        Ok(())
     }
 }
@@ -1,6 +1,10 @@
 use crate::Handle;
 use crate::Material;
-use glium;
+use crate::MaterialKey;
+use crate::RenderTarget;
+use crate::Result;
+use crate::Viewport;
+use crate::{BlendMode, DrawState};
 use glium::uniform;
 
 pub use super::Model;
@@ -8,48 +12,75 @@ pub use super::Model;
 /// Implementor of `Material`, used to draw models in the `simple` module
 pub struct SimpleMaterial {
     program: glium::program::Program,
+    /// State used for models which don't specify their own
+    pub state: DrawState,
 }
 
 impl SimpleMaterial {
-    /// Create a new instance
-    pub fn new(display: &glium::Display) -> Self {
+    /// Create a new instance, failing with the driver's log if the shaders
+    /// don't compile
+    pub fn new<F: glium::backend::Facade + ?Sized>(facade: &F) -> Result<Self> {
         let program = glium::Program::from_source(
-            display,
+            facade,
             include_str!("shaders/triangle_150_vs.glsl"),
             include_str!("shaders/triangle_150_fs.glsl"),
             None,
-        )
-        .unwrap();
+        )?;
 
-        SimpleMaterial { program }
+        Ok(SimpleMaterial {
+            program,
+            state: DrawState::default(),
+        })
+    }
+
+    /// Update the state used for models which don't specify their own
+    pub fn with_state(mut self, state: DrawState) -> Self {
+        self.state = state;
+        self
     }
 }
 
 impl Material<Model> for SimpleMaterial {
     fn draw(
         &self,
-        target: &mut glium::Frame,
+        target: &mut RenderTarget,
         model: Handle<Model>,
         u_view_proj: [[f32; 4]; 4],
         u_world: [[f32; 4]; 4],
-    ) {
+        viewport: Option<&Viewport>,
+    ) -> Result<()> {
         use glium::Surface;
 
         let md = model.borrow_mut();
 
+        let state = md.state.unwrap_or(self.state);
+        let rect = viewport.map(|viewport| target.viewport_rect(viewport));
+        let params = glium::DrawParameters {
+            viewport: rect,
+            scissor: rect,
+            ..state.parameters(target.depth_mode())
+        };
+
         let uniforms = uniform! {
             u_ViewProj: u_view_proj,
             u_World: u_world
         };
 
-        target
-            .draw(
-                &md.vertex_buffer,
-                &md.indices,
-                &self.program,
-                &uniforms,
-                &Default::default(),
-            )
-            .unwrap();
+        target.draw(
+            &md.vertex_buffer,
+            md.indices,
+            &self.program,
+            &uniforms,
+            &params,
+        )?;
+        Ok(())
+    }
+
+    fn is_transparent(&self, model: &Model) -> bool {
+        model.state.unwrap_or(self.state).blend != BlendMode::Opaque
+    }
+
+    fn sort_key(&self, model: &Model) -> MaterialKey {
+        MaterialKey::new(&self.program, model.state.unwrap_or(self.state))
     }
 }
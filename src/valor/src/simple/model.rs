@@ -1,8 +1,9 @@
 use super::vertex::Vertex;
+use crate::DrawState;
 use crate::Handle;
 use crate::Renderer;
+use crate::Result;
 use cgmath::{Matrix4, One, Vector3};
-use glium;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -19,12 +20,14 @@ pub struct Model {
     // pub gpu_data: GpuData,
     /// Flag to indicate the model gpu data needs to be refreshed
     pub is_dirty: bool,
+    /// State to draw with instead of the material's
+    pub state: Option<DrawState>,
 }
 
 impl Model {
     /// Create a new instance of the model
-    pub fn new(renderer: &mut Renderer, vertices: &[Vertex]) -> Handle<Self> {
-        let vertex_buffer = glium::VertexBuffer::new(&renderer.display, &vertices).unwrap();
+    pub fn new(renderer: &mut Renderer, vertices: &[Vertex]) -> Result<Handle<Self>> {
+        let vertex_buffer = glium::VertexBuffer::new(renderer.facade(), vertices)?;
         let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
 
         let transform: Matrix4<f32> = Matrix4::one();
@@ -35,9 +38,10 @@ impl Model {
             vertex_buffer,
             indices,
             is_dirty: false,
+            state: None,
         };
 
-        Rc::new(RefCell::new(Box::new(model)))
+        Ok(Rc::new(RefCell::new(Box::new(model))))
     }
 
     /*
@@ -1,3 +1,6 @@
+// `implement_vertex!` expands to code using `std::mem::uninitialized`
+#![allow(deprecated)]
+
 use glium::implement_vertex;
 
 #[derive(Copy, Clone)]
@@ -0,0 +1,293 @@
+use crate::{DepthMode, Error, Result, Viewport};
+use glium::backend::Facade;
+use glium::framebuffer::{MultiOutputFrameBuffer, SimpleFrameBuffer};
+use glium::texture::{
+    DepthFormat, DepthTexture2d, MipmapsOption, Texture2d, UncompressedFloatFormat,
+};
+use glium::uniforms::MagnifySamplerFilter;
+use glium::{BlitTarget, DrawError, DrawParameters, Program, Rect, Surface};
+
+/// A surface materials draw into.
+///
+/// The `Renderer` hands one out for the window's back buffer each frame, or
+/// for the textures of a `TextureTarget`. It implements `glium::Surface`, so
+/// anything glium can draw may be drawn into it too.
+///
+/// The target carries the depth layout it was cleared for, so materials test
+/// depth the same way as the renderer without being told separately.
+pub struct RenderTarget<'a> {
+    surface: TargetSurface<'a>,
+    depth_mode: DepthMode,
+}
+
+/// The surface a `RenderTarget` wraps
+pub(crate) enum TargetSurface<'a> {
+    /// The back buffer of a window
+    Frame(glium::Frame),
+    /// An offscreen framebuffer with a single color attachment
+    Framebuffer(SimpleFrameBuffer<'a>),
+    /// An offscreen framebuffer with several color attachments, each written
+    /// by the fragment shader output of the same name
+    MultiOutput(MultiOutputFrameBuffer<'a>),
+}
+
+/// Textures to draw into instead of the window, for in-world monitors,
+/// minimaps, shadow maps and post-processing.
+///
+/// A target has one or more color textures and a 32-bit float depth texture,
+/// all the same size, which can be sampled by later draws once drawing into
+/// them is done.
+///
+/// # Example
+///
+/// ```no_run
+/// # use valor::{TextureTarget, ValorBuilder};
+/// # let (_, mut renderer) = ValorBuilder::new().finish()?;
+/// let minimap = TextureTarget::new(renderer.facade(), 256, 256)?;
+///
+/// renderer.render_to(&minimap, |target| {
+///     // Draw the scene from above
+///     Ok(())
+/// })?;
+///
+/// // Sample `minimap.color()` when drawing the frame
+/// # Ok::<(), valor::Error>(())
+/// ```
+pub struct TextureTarget {
+    colors: Vec<(String, Texture2d)>,
+    depth: DepthTexture2d,
+}
+
+impl TextureTarget {
+    /// Create a target with a single RGBA color texture
+    pub fn new<F: Facade + ?Sized>(facade: &F, width: u32, height: u32) -> Result<Self> {
+        Self::with_outputs(facade, width, height, &["color"])
+    }
+
+    /// Create a target with a color texture for each named fragment shader
+    /// output, e.g. for a G-buffer. Fails with `Error::NoColorOutputs` if
+    /// `outputs` is empty.
+    pub fn with_outputs<F: Facade + ?Sized>(
+        facade: &F,
+        width: u32,
+        height: u32,
+        outputs: &[&str],
+    ) -> Result<Self> {
+        if outputs.is_empty() {
+            return Err(Error::NoColorOutputs);
+        }
+
+        let colors = outputs
+            .iter()
+            .map(|name| {
+                let texture = Texture2d::empty_with_format(
+                    facade,
+                    UncompressedFloatFormat::U8U8U8U8,
+                    MipmapsOption::NoMipmap,
+                    width,
+                    height,
+                )?;
+                Ok((name.to_string(), texture))
+            })
+            .collect::<Result<_>>()?;
+
+        let depth = DepthTexture2d::empty_with_format(
+            facade,
+            DepthFormat::F32,
+            MipmapsOption::NoMipmap,
+            width,
+            height,
+        )?;
+
+        Ok(TextureTarget { colors, depth })
+    }
+
+    /// The width and height of the textures
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.depth.dimensions()
+    }
+
+    /// The first color texture
+    pub fn color(&self) -> &Texture2d {
+        &self.colors[0].1
+    }
+
+    /// The color texture for the named output, if there is one
+    pub fn output(&self, name: &str) -> Option<&Texture2d> {
+        self.colors
+            .iter()
+            .find(|(output, _)| output == name)
+            .map(|(_, texture)| texture)
+    }
+
+    /// The depth texture
+    pub fn depth(&self) -> &DepthTexture2d {
+        &self.depth
+    }
+
+    /// A surface drawing into the textures, with depth laid out as
+    /// `depth_mode`
+    pub fn target<F: Facade + ?Sized>(
+        &self,
+        facade: &F,
+        depth_mode: DepthMode,
+    ) -> Result<RenderTarget<'_>> {
+        let surface = if self.colors.len() == 1 {
+            let framebuffer =
+                SimpleFrameBuffer::with_depth_buffer(facade, self.color(), &self.depth)?;
+            TargetSurface::Framebuffer(framebuffer)
+        } else {
+            let outputs = self
+                .colors
+                .iter()
+                .map(|(name, texture)| (name.as_str(), texture));
+            let framebuffer =
+                MultiOutputFrameBuffer::with_depth_buffer(facade, outputs, &self.depth)?;
+            TargetSurface::MultiOutput(framebuffer)
+        };
+
+        Ok(RenderTarget::new(surface, depth_mode))
+    }
+}
+
+impl<'a> RenderTarget<'a> {
+    pub(crate) fn new(surface: TargetSurface<'a>, depth_mode: DepthMode) -> Self {
+        RenderTarget {
+            surface,
+            depth_mode,
+        }
+    }
+
+    /// The depth layout the target was cleared for, which materials should
+    /// test depth against
+    pub fn depth_mode(&self) -> DepthMode {
+        self.depth_mode
+    }
+
+    /// The rectangle of the target covered by `viewport`, rounded to whole
+    /// pixels, for use as glium's `viewport` and `scissor` draw parameters.
+    /// glium measures rectangles from the bottom of the target rather than
+    /// the top.
+    pub fn viewport_rect(&self, viewport: &Viewport) -> Rect {
+        let pixels = |value: f32| value.round().max(0.0) as u32;
+        let height = self.get_dimensions().1;
+
+        Rect {
+            left: pixels(viewport.x),
+            bottom: height.saturating_sub(pixels(viewport.y + viewport.height)),
+            width: pixels(viewport.width),
+            height: pixels(viewport.height),
+        }
+    }
+
+    /// Present the frame if the target is the window's back buffer
+    pub(crate) fn finish(self) -> Result<()> {
+        match self.surface {
+            TargetSurface::Frame(frame) => Ok(frame.finish()?),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Forward a method call to whichever surface the target wraps
+macro_rules! forward {
+    ($target:expr, $surface:ident => $call:expr) => {
+        match $target {
+            TargetSurface::Frame($surface) => $call,
+            TargetSurface::Framebuffer($surface) => $call,
+            TargetSurface::MultiOutput($surface) => $call,
+        }
+    };
+}
+
+impl<'a> Surface for RenderTarget<'a> {
+    fn clear(
+        &mut self,
+        rect: Option<&Rect>,
+        color: Option<(f32, f32, f32, f32)>,
+        color_srgb: bool,
+        depth: Option<f32>,
+        stencil: Option<i32>,
+    ) {
+        forward!(&mut self.surface, surface => {
+            surface.clear(rect, color, color_srgb, depth, stencil)
+        })
+    }
+
+    fn get_dimensions(&self) -> (u32, u32) {
+        forward!(&self.surface, surface => surface.get_dimensions())
+    }
+
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        forward!(&self.surface, surface => surface.get_depth_buffer_bits())
+    }
+
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        forward!(&self.surface, surface => surface.get_stencil_buffer_bits())
+    }
+
+    fn draw<'b, 'c, V, I, U>(
+        &mut self,
+        vertices: V,
+        indices: I,
+        program: &Program,
+        uniforms: &U,
+        draw_parameters: &DrawParameters,
+    ) -> std::result::Result<(), DrawError>
+    where
+        V: glium::vertex::MultiVerticesSource<'c>,
+        I: Into<glium::index::IndicesSource<'b>>,
+        U: glium::uniforms::Uniforms,
+    {
+        forward!(&mut self.surface, surface => {
+            surface.draw(vertices, indices, program, uniforms, draw_parameters)
+        })
+    }
+
+    fn blit_from_frame(
+        &self,
+        source_rect: &Rect,
+        target_rect: &BlitTarget,
+        filter: MagnifySamplerFilter,
+    ) {
+        forward!(&self.surface, surface => surface.blit_from_frame(source_rect, target_rect, filter))
+    }
+
+    fn blit_from_simple_framebuffer(
+        &self,
+        source: &SimpleFrameBuffer,
+        source_rect: &Rect,
+        target_rect: &BlitTarget,
+        filter: MagnifySamplerFilter,
+    ) {
+        forward!(&self.surface, surface => {
+            surface.blit_from_simple_framebuffer(source, source_rect, target_rect, filter)
+        })
+    }
+
+    fn blit_from_multioutput_framebuffer(
+        &self,
+        source: &MultiOutputFrameBuffer,
+        source_rect: &Rect,
+        target_rect: &BlitTarget,
+        filter: MagnifySamplerFilter,
+    ) {
+        forward!(&self.surface, surface => {
+            surface.blit_from_multioutput_framebuffer(source, source_rect, target_rect, filter)
+        })
+    }
+
+    fn blit_color<S>(
+        &self,
+        source_rect: &Rect,
+        target: &S,
+        target_rect: &BlitTarget,
+        filter: MagnifySamplerFilter,
+    ) where
+        S: Surface,
+    {
+        forward!(&self.surface, surface => {
+            surface.blit_color(source_rect, target, target_rect, filter)
+        })
+    }
+}
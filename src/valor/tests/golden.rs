@@ -0,0 +1,300 @@
+//! Golden-image regression tests for `simple::Material`.
+//!
+//! Each test renders a reference scene with a headless renderer and compares
+//! it against `tests/golden/<name>.png`. Small differences, such as those from
+//! antialiasing or driver rounding, are tolerated; anything else fails the
+//! test and writes the rendered frame and a diff image next to the test
+//! binary's temporary directory.
+//!
+//! Run with `VALOR_BLESS=1` to accept the current output as the new reference;
+//! without it a missing reference fails the test.
+//!
+//! The tests need a headless OpenGL context, which on Linux means OSMesa.
+//! Where one can't be created they pass after printing why they were skipped,
+//! unless `VALOR_REQUIRE_GL` is set, as it is in CI, in which case they fail.
+//!
+//! `simple::Material` only takes vertex colors, so the scenes are colored and
+//! shaded through their vertices rather than with textures or lights.
+
+use cgmath::prelude::*;
+use cgmath::{Deg, Matrix4, Quaternion, Vector3};
+use std::env;
+use std::path::PathBuf;
+use valor::simple::{Material as SimpleMaterial, Model, Vertex};
+use valor::{Image, Material, Renderer, ValorBuilder};
+use valor_camera::CameraBuilder;
+
+const SIZE: f64 = 128.0;
+const CLEAR_COLOR: [f32; 4] = [0.1, 0.2, 0.3, 1.0];
+
+/// Color distance, as a fraction of the largest possible, below which two
+/// pixels look the same
+const THRESHOLD: f32 = 0.1;
+/// Fraction of pixels allowed to differ before the images are considered
+/// different
+const MAX_MISMATCH: f32 = 0.001;
+/// Largest possible value of `delta`
+const MAX_DELTA: f32 = 35215.0;
+
+/// A reference scene: a model seen from `eye`, looking at the origin
+struct Scene {
+    vertices: Vec<Vertex>,
+    eye: Vector3<f32>,
+    world: Matrix4<f32>,
+}
+
+impl Scene {
+    /// The camera's view and projection
+    fn view_proj(&self) -> [[f32; 4]; 4] {
+        let mut camera = CameraBuilder::new().with_aspect_ratio(1.0).finish();
+        camera.set_position(self.eye);
+        camera.look_at(Vector3::zero(), Vector3::unit_y());
+        camera.get_view_proj().into()
+    }
+}
+
+/// Create a headless renderer for the test `name`, or `None` if there's no
+/// OpenGL context to render with and it isn't required
+fn renderer(name: &str) -> Option<Renderer> {
+    let result = ValorBuilder::new()
+        .with_dimensions(SIZE, SIZE)
+        .with_clear_color(CLEAR_COLOR)
+        .finish_headless();
+
+    match result {
+        Ok(renderer) => Some(renderer),
+        Err(error) if env::var_os("VALOR_REQUIRE_GL").is_some() => {
+            panic!("no headless OpenGL context: {}", error)
+        }
+        Err(error) => {
+            eprintln!(
+                "skipping {}: no headless OpenGL context ({}), set VALOR_REQUIRE_GL=1 to fail instead",
+                name, error
+            );
+            None
+        }
+    }
+}
+
+/// Render `scene` and compare it against the reference image called `name`
+fn check(name: &str, scene: &Scene) {
+    if let Some(mut renderer) = renderer(name) {
+        render(&mut renderer, scene);
+        assert_golden(&renderer, name);
+    }
+}
+
+/// Draw `scene` once with the simple material
+fn render(renderer: &mut Renderer, scene: &Scene) {
+    let material = SimpleMaterial::new(renderer.facade()).unwrap();
+    let model = Model::new(renderer, &scene.vertices).unwrap();
+
+    let view_proj = scene.view_proj();
+    let world: [[f32; 4]; 4] = scene.world.into();
+
+    renderer
+        .render(|target| material.draw(target, model.clone(), view_proj, world, None))
+        .unwrap();
+}
+
+/// Compare the last frame drawn against the reference image called `name`
+fn assert_golden(renderer: &Renderer, name: &str) {
+    let actual = renderer.read_pixels();
+
+    let reference_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.png", name));
+
+    if env::var_os("VALOR_BLESS").is_some() {
+        std::fs::create_dir_all(reference_path.parent().unwrap()).unwrap();
+        actual.save_png(&reference_path).unwrap();
+        eprintln!("wrote reference image {}", reference_path.display());
+        return;
+    }
+
+    let expected = Image::load_png(&reference_path).unwrap_or_else(|error| {
+        panic!(
+            "can't read reference image {} ({}), run with VALOR_BLESS=1 to create it",
+            reference_path.display(),
+            error
+        )
+    });
+    assert_eq!(
+        (actual.width, actual.height),
+        (expected.width, expected.height),
+        "{} was rendered at a different size to its reference",
+        name
+    );
+
+    let (mismatched, diff) = compare(&expected, &actual);
+    let allowed = (MAX_MISMATCH * (actual.width * actual.height) as f32) as usize;
+
+    if mismatched > allowed {
+        let out = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden");
+        std::fs::create_dir_all(&out).unwrap();
+
+        let actual_path = out.join(format!("{}.actual.png", name));
+        let diff_path = out.join(format!("{}.diff.png", name));
+        actual.save_png(&actual_path).unwrap();
+        diff.save_png(&diff_path).unwrap();
+
+        panic!(
+            "{} differs from its reference in {} pixels, see {} and {}",
+            name,
+            mismatched,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+/// Count the pixels which look different, and draw them in red over a faded
+/// copy of `expected`
+fn compare(expected: &Image, actual: &Image) -> (usize, Image) {
+    let mut mismatched = 0;
+    let mut diff = Vec::with_capacity(expected.data.len());
+
+    for (a, b) in expected.data.chunks(4).zip(actual.data.chunks(4)) {
+        if delta(a, b) > MAX_DELTA * THRESHOLD * THRESHOLD {
+            mismatched += 1;
+            diff.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let (y, _, _) = yiq(a);
+            let faded = (255.0 - (255.0 - y) * 0.1) as u8;
+            diff.extend_from_slice(&[faded, faded, faded, 255]);
+        }
+    }
+
+    (
+        mismatched,
+        Image::new(expected.width, expected.height, diff).unwrap(),
+    )
+}
+
+/// Perceived squared distance between two RGBA pixels, measured in YIQ space
+/// where brightness counts for more than hue
+fn delta(a: &[u8], b: &[u8]) -> f32 {
+    let (y1, i1, q1) = yiq(a);
+    let (y2, i2, q2) = yiq(b);
+    let (y, i, q) = (y1 - y2, i1 - i2, q1 - q2);
+
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+/// Convert a pixel to YIQ, blending it over white first so transparent pixels
+/// compare by how they appear
+fn yiq(pixel: &[u8]) -> (f32, f32, f32) {
+    let alpha = f32::from(pixel[3]) / 255.0;
+    let blend = |c: u8| 255.0 + (f32::from(c) - 255.0) * alpha;
+    let (r, g, b) = (blend(pixel[0]), blend(pixel[1]), blend(pixel[2]));
+
+    (
+        0.299 * r + 0.587 * g + 0.114 * b,
+        0.596 * r - 0.274 * g - 0.322 * b,
+        0.211 * r - 0.523 * g + 0.312 * b,
+    )
+}
+
+/// Two triangles covering the square between `min` and `max` on the XY plane
+fn quad(min: (f32, f32), max: (f32, f32), color: [f32; 3]) -> Vec<Vertex> {
+    vec![
+        Vertex::new(min.0, min.1, 0.0, color),
+        Vertex::new(max.0, min.1, 0.0, color),
+        Vertex::new(max.0, max.1, 0.0, color),
+        Vertex::new(min.0, min.1, 0.0, color),
+        Vertex::new(max.0, max.1, 0.0, color),
+        Vertex::new(min.0, max.1, 0.0, color),
+    ]
+}
+
+fn triangle_scene() -> Scene {
+    Scene {
+        vertices: vec![
+            Vertex::new(-0.5, -0.5, 0.0, [1.0, 0.0, 0.0]),
+            Vertex::new(0.5, -0.5, 0.0, [0.0, 1.0, 0.0]),
+            Vertex::new(0.0, 0.5, 0.0, [0.0, 0.0, 1.0]),
+        ],
+        eye: Vector3::new(0.0, 0.0, 2.0),
+        world: Matrix4::identity(),
+    }
+}
+
+fn checkerboard_quad_scene() -> Scene {
+    // An 8x8 checkerboard
+    let cells = 8;
+    let size = 1.0 / cells as f32;
+    let mut vertices = Vec::new();
+    for row in 0..cells {
+        for column in 0..cells {
+            let color = if (row + column) % 2 == 0 {
+                [0.9, 0.9, 0.9]
+            } else {
+                [0.2, 0.2, 0.2]
+            };
+            let min = (column as f32 * size - 0.5, row as f32 * size - 0.5);
+            let max = (min.0 + size, min.1 + size);
+            vertices.extend(quad(min, max, color));
+        }
+    }
+
+    Scene {
+        vertices,
+        eye: Vector3::new(0.0, 0.0, 1.5),
+        world: Matrix4::identity(),
+    }
+}
+
+fn shaded_cube_scene() -> Scene {
+    let light: Vector3<f32> = Vector3::new(0.5, 1.0, 0.8).normalize();
+    let albedo = Vector3::new(0.8, 0.5, 0.2);
+
+    // Each face is a quad on the XY plane, turned to face outwards and given
+    // a flat color by how directly it faces the light
+    let faces = [
+        Quaternion::one(),
+        Quaternion::from_angle_y(Deg(90.0)),
+        Quaternion::from_angle_y(Deg(180.0)),
+        Quaternion::from_angle_y(Deg(-90.0)),
+        Quaternion::from_angle_x(Deg(90.0)),
+        Quaternion::from_angle_x(Deg(-90.0)),
+    ];
+
+    let mut vertices = Vec::new();
+    for rotation in faces.iter() {
+        let normal = rotation.rotate_vector(Vector3::unit_z());
+        let diffuse = 0.2 + 0.8 * normal.dot(light).max(0.0);
+        let color: [f32; 3] = (albedo * diffuse).into();
+
+        let corners = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)];
+        let corners: Vec<_> = corners
+            .iter()
+            .map(|&(x, y)| rotation.rotate_vector(Vector3::new(x, y, 0.5)))
+            .collect();
+
+        for &index in [0, 1, 2, 0, 2, 3].iter() {
+            let corner = corners[index];
+            vertices.push(Vertex::new(corner.x, corner.y, corner.z, color));
+        }
+    }
+
+    Scene {
+        vertices,
+        eye: Vector3::new(1.5, 1.2, 2.0),
+        world: Matrix4::from_angle_y(Deg(20.0)),
+    }
+}
+
+#[test]
+fn triangle() {
+    check("triangle", &triangle_scene());
+}
+
+#[test]
+fn checkerboard_quad() {
+    check("checkerboard_quad", &checkerboard_quad_scene());
+}
+
+#[test]
+fn shaded_cube() {
+    check("shaded_cube", &shaded_cube_scene());
+}
@@ -1,18 +1,46 @@
-use crate::Camera;
-use cgmath::{perspective, Deg};
+use crate::{Camera, Projection};
 
 const DEFAULT_FOV: f32 = 80.0;
 const DEFAULT_ASPECT: f32 = 4.0 / 3.0;
 const DEFAULT_NEAR: f32 = 0.1;
 const DEFAULT_FAR: f32 = 50.0;
 const DEFAULT_SENSITIVITY: f32 = 1.3 / 20.0;
+const DEFAULT_ZOOM: f32 = 1.0;
+
+/// The shape of the view volume the camera will be built with
+enum Volume {
+  Perspective,
+  Frustum {
+    left: f32,
+    right: f32,
+    bottom: f32,
+    top: f32,
+  },
+  Orthographic {
+    left: f32,
+    right: f32,
+    bottom: f32,
+    top: f32,
+  },
+  OrthographicHeight(f32),
+}
 
 pub struct CameraBuilder {
   fov: f32,
   aspect_ratio: f32,
   near_plane: f32,
   far_plane: f32,
+  infinite_far_plane: bool,
   sensitivity: f32,
+  zoom: f32,
+  volume: Volume,
+  reverse_z: bool,
+}
+
+impl Default for CameraBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 impl CameraBuilder {
@@ -22,7 +50,11 @@ impl CameraBuilder {
       aspect_ratio: DEFAULT_ASPECT,
       near_plane: DEFAULT_NEAR,
       far_plane: DEFAULT_FAR,
+      infinite_far_plane: false,
       sensitivity: DEFAULT_SENSITIVITY,
+      zoom: DEFAULT_ZOOM,
+      volume: Volume::Perspective,
+      reverse_z: false,
     }
   }
 
@@ -43,6 +75,7 @@ impl CameraBuilder {
 
   pub fn with_far_plane(mut self, far_plane: f32) -> Self {
     self.far_plane = far_plane;
+    self.infinite_far_plane = false;
     self
   }
 
@@ -51,14 +84,118 @@ impl CameraBuilder {
     self
   }
 
+  /// Remove the far plane of a perspective or frustum projection, so nothing
+  /// is clipped for being too distant.
+  ///
+  /// Orthographic projections can't extend to infinity, so they keep the far
+  /// plane given to `with_far_plane`.
+  pub fn with_infinite_far_plane(mut self) -> Self {
+    self.infinite_far_plane = true;
+    self
+  }
+
+  /// Use a reverse-Z projection. The renderer must be configured with the
+  /// matching depth mode. See `Camera::set_reverse_z`.
+  pub fn with_reverse_z(mut self, reverse_z: bool) -> Self {
+    self.reverse_z = reverse_z;
+    self
+  }
+
+  /// Use a perspective projection. This is the default.
+  pub fn with_perspective(mut self) -> Self {
+    self.volume = Volume::Perspective;
+    self
+  }
+
+  /// Use a perspective projection with explicit bounds on the near plane,
+  /// which need not be centered on the view axis. The field of view and
+  /// aspect ratio are ignored.
+  pub fn with_frustum(mut self, left: f32, right: f32, bottom: f32, top: f32) -> Self {
+    self.volume = Volume::Frustum {
+      left,
+      right,
+      bottom,
+      top,
+    };
+    self
+  }
+
+  /// Use an orthographic projection with explicit view-space bounds
+  pub fn with_orthographic(mut self, left: f32, right: f32, bottom: f32, top: f32) -> Self {
+    self.volume = Volume::Orthographic {
+      left,
+      right,
+      bottom,
+      top,
+    };
+    self
+  }
+
+  /// Use an orthographic projection `height` units tall, with the width
+  /// derived from the aspect ratio
+  pub fn with_orthographic_height(mut self, height: f32) -> Self {
+    self.volume = Volume::OrthographicHeight(height);
+    self
+  }
+
+  pub fn with_zoom(mut self, zoom: f32) -> Self {
+    self.zoom = zoom;
+    self
+  }
+
   pub fn finish(self) -> Camera {
-    let perspective = perspective(
-      Deg(self.fov),
-      self.aspect_ratio,
-      self.near_plane,
-      self.far_plane,
-    );
-
-    Camera::new(perspective, self.sensitivity)
+    let near = self.near_plane;
+    let far = self.far_plane;
+    let perspective_far = if self.infinite_far_plane {
+      f32::INFINITY
+    } else {
+      far
+    };
+
+    let projection = match self.volume {
+      Volume::Perspective => Projection::Perspective {
+        fov: self.fov,
+        aspect_ratio: self.aspect_ratio,
+        near,
+        far: perspective_far,
+      },
+      Volume::Frustum {
+        left,
+        right,
+        bottom,
+        top,
+      } => Projection::Frustum {
+        left,
+        right,
+        bottom,
+        top,
+        near,
+        far: perspective_far,
+      },
+      Volume::Orthographic {
+        left,
+        right,
+        bottom,
+        top,
+      } => Projection::Orthographic {
+        left,
+        right,
+        bottom,
+        top,
+        near,
+        far,
+      },
+      Volume::OrthographicHeight(height) => Projection::OrthographicHeight {
+        height,
+        aspect_ratio: self.aspect_ratio,
+        near,
+        far,
+      },
+    };
+
+    let mut camera = Camera::new(projection, self.sensitivity);
+    camera.set_zoom(self.zoom);
+    camera.set_reverse_z(self.reverse_z);
+    camera
   }
 }
@@ -0,0 +1,220 @@
+use crate::Camera;
+use cgmath::prelude::*;
+use cgmath::{ortho, Matrix4, Point3, Vector3};
+
+const DEFAULT_CASCADES: usize = 4;
+const DEFAULT_LAMBDA: f32 = 0.75;
+const DEFAULT_RESOLUTION: u32 = 2048;
+/// Granularity the bounding radius of each cascade is rounded up to, so that
+/// floating point noise can't change the size of the shadow map's texels
+const RADIUS_STEP: f32 = 1.0 / 16.0;
+
+/// Distances from the camera at which to split its view into `count`
+/// cascades, using the practical split scheme.
+///
+/// `lambda` blends between a uniform split at `0.0`, which wastes resolution
+/// close to the camera, and a logarithmic split at `1.0`, which wastes it far
+/// away. The result holds `count + 1` increasing distances, starting at
+/// `near` and ending at `far`.
+///
+/// Returns `None` unless there is at least one cascade and
+/// `0 < near < far < ∞`.
+pub fn cascade_splits(near: f32, far: f32, count: usize, lambda: f32) -> Option<Vec<f32>> {
+    if count == 0 || !(near > 0.0 && near < far && far.is_finite()) {
+        return None;
+    }
+
+    let splits = (0..=count)
+        .map(|i| {
+            let fraction = i as f32 / count as f32;
+            let logarithmic = near * (far / near).powf(fraction);
+            let uniform = near + (far - near) * fraction;
+            lambda * logarithmic + (1.0 - lambda) * uniform
+        })
+        .collect();
+
+    Some(splits)
+}
+
+/// One slice of a camera's view, and the directional light projection which
+/// covers it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cascade {
+    /// Distance from the camera at which the cascade begins
+    pub near: f32,
+    /// Distance from the camera at which the cascade ends
+    pub far: f32,
+    /// World-space corners of the slice, as given by `Camera::frustum_corners`
+    pub corners: [Vector3<f32>; 8],
+    /// The light's view, which only rotates world space to face the light
+    pub view: Matrix4<f32>,
+    /// Orthographic projection enclosing the slice
+    pub projection: Matrix4<f32>,
+}
+
+impl Cascade {
+    /// The combined light view and projection to render the shadow map with
+    pub fn view_proj(&self) -> Matrix4<f32> {
+        self.projection * self.view
+    }
+}
+
+/// Cascaded shadow map layout for a directional light.
+///
+/// Each cascade's light projection is fitted to a sphere around its slice of
+/// the camera's view rather than the slice itself, so its size doesn't change
+/// as the camera turns, and is moved in whole shadow map texels, so shadow
+/// edges don't shimmer as the camera moves.
+///
+/// # Example
+///
+/// ```
+/// # use valor_camera::{CameraBuilder, ShadowCascades};
+/// # use cgmath::Vector3;
+/// let camera = CameraBuilder::new().finish();
+/// let shadows = ShadowCascades::new().with_max_distance(40.0);
+///
+/// let cascades = shadows.cascades(&camera, Vector3::new(-1.0, -2.0, -1.0));
+/// for cascade in cascades.unwrap() {
+///     let light_view_proj: [[f32; 4]; 4] = cascade.view_proj().into();
+///     // Render shadow casters into this cascade's shadow map
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowCascades {
+    /// Number of cascades
+    pub count: usize,
+    /// Blend between uniform and logarithmic splits, see `cascade_splits`
+    pub lambda: f32,
+    /// Distance beyond which nothing receives shadows. The camera's far plane
+    /// is used when this is `None`.
+    pub max_distance: Option<f32>,
+    /// Width and height of each cascade's shadow map, in texels
+    pub resolution: u32,
+    /// Extra depth included towards the light, so casters outside the view
+    /// still cast shadows into it
+    pub caster_margin: f32,
+}
+
+impl Default for ShadowCascades {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShadowCascades {
+    pub fn new() -> Self {
+        ShadowCascades {
+            count: DEFAULT_CASCADES,
+            lambda: DEFAULT_LAMBDA,
+            max_distance: None,
+            resolution: DEFAULT_RESOLUTION,
+            caster_margin: 0.0,
+        }
+    }
+
+    pub fn with_count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn with_lambda(mut self, lambda: f32) -> Self {
+        self.lambda = lambda;
+        self
+    }
+
+    pub fn with_max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = Some(max_distance);
+        self
+    }
+
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    pub fn with_caster_margin(mut self, caster_margin: f32) -> Self {
+        self.caster_margin = caster_margin;
+        self
+    }
+
+    /// The split distances for `camera`, see `cascade_splits`.
+    ///
+    /// Returns `None` if there are no cascades, or if the camera has an
+    /// infinite far plane and no `max_distance` is set.
+    pub fn splits(&self, camera: &Camera) -> Option<Vec<f32>> {
+        let projection = camera.projection();
+        let far = self.max_distance.unwrap_or_else(|| projection.far());
+
+        cascade_splits(projection.near(), far, self.count, self.lambda)
+    }
+
+    /// Fit a cascade to each slice of `camera`'s view, for a light shining in
+    /// `direction`. Returns `None` when `splits` does.
+    pub fn cascades(&self, camera: &Camera, direction: Vector3<f32>) -> Option<Vec<Cascade>> {
+        let view = light_view(direction);
+
+        let cascades = self
+            .splits(camera)?
+            .windows(2)
+            .map(|range| {
+                let (near, far) = (range[0], range[1]);
+                let corners = camera.frustum_corners(near, far);
+                let projection = self.fit(view, &corners);
+
+                Cascade {
+                    near,
+                    far,
+                    corners,
+                    view,
+                    projection,
+                }
+            })
+            .collect();
+
+        Some(cascades)
+    }
+
+    /// Orthographic projection around the bounding sphere of `corners`, in
+    /// the light's view, snapped to the shadow map's texel grid
+    fn fit(&self, view: Matrix4<f32>, corners: &[Vector3<f32>; 8]) -> Matrix4<f32> {
+        let center = corners
+            .iter()
+            .fold(Vector3::zero(), |sum, corner| sum + corner)
+            / 8.0;
+        let radius = corners
+            .iter()
+            .map(|corner| corner.distance(center))
+            .fold(0.0, f32::max);
+        let radius = (radius / RADIUS_STEP).ceil() * RADIUS_STEP;
+
+        let texel = 2.0 * radius / self.resolution as f32;
+        let center = (view * center.extend(1.0)).truncate();
+        let x = (center.x / texel).floor() * texel;
+        let y = (center.y / texel).floor() * texel;
+
+        // The light looks down -Z, so depth in front of it is -z
+        ortho(
+            x - radius,
+            x + radius,
+            y - radius,
+            y + radius,
+            -center.z - radius - self.caster_margin,
+            -center.z + radius,
+        )
+    }
+}
+
+/// A view which turns the world to face a light shining in `direction`,
+/// without translating it. Keeping the origin fixed means the light's texel
+/// grid only moves when the light itself turns.
+fn light_view(direction: Vector3<f32>) -> Matrix4<f32> {
+    let direction = direction.normalize();
+    let up = if direction.y.abs() > 0.99 {
+        Vector3::unit_z()
+    } else {
+        Vector3::unit_y()
+    };
+
+    Matrix4::look_at_dir(Point3::origin(), direction, up)
+}
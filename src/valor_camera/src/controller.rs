@@ -0,0 +1,56 @@
+use crate::Camera;
+
+/// Something the player can hold down to steer a camera, independent of the
+/// key or button bound to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Forward,
+    Backward,
+    Left,
+    Right,
+    Up,
+    Down,
+    /// Held while dragging to rotate around a target
+    Rotate,
+    /// Held while dragging to move a target across the view
+    Pan,
+}
+
+/// Input for a `CameraController`, translated from whatever windowing or
+/// input library is in use.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraInput {
+    /// An action started (`active` is `true`) or stopped
+    Action { action: Action, active: bool },
+    /// Relative pointer movement, in pixels
+    Look { dx: f32, dy: f32 },
+    /// Scroll steps, positive towards the scene
+    Zoom(f32),
+}
+
+/// A style of camera movement driven by player input.
+///
+/// Input is fed in as it arrives with `handle_input`, then `update` is called
+/// once per frame to move the camera.
+///
+/// # Example
+///
+/// ```
+/// # use valor_camera::{Action, CameraBuilder, CameraController, CameraInput, FlyController};
+/// let mut camera = CameraBuilder::new().finish();
+/// let mut controller = FlyController::new();
+///
+/// controller.handle_input(&CameraInput::Action {
+///     action: Action::Forward,
+///     active: true,
+/// });
+/// controller.update(1.0 / 60.0, &mut camera);
+/// ```
+pub trait CameraController {
+    /// Record a piece of input
+    fn handle_input(&mut self, input: &CameraInput);
+
+    /// Move `camera` according to the input received, `dt` being the seconds
+    /// elapsed since the last update
+    fn update(&mut self, dt: f32, camera: &mut Camera);
+}
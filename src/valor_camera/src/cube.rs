@@ -0,0 +1,135 @@
+use crate::Projection;
+use cgmath::{Matrix4, Point3, Vector3};
+
+const DEFAULT_NEAR: f32 = 0.1;
+const DEFAULT_FAR: f32 = 50.0;
+
+/// One face of a cubemap, in the order OpenGL lays out cubemap layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl CubeFace {
+    /// Every face, in layer order
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+
+    /// The layer index of this face within a cubemap
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The direction a camera rendering this face looks in
+    pub fn direction(self) -> Vector3<f32> {
+        match self {
+            CubeFace::PositiveX => Vector3::unit_x(),
+            CubeFace::NegativeX => -Vector3::unit_x(),
+            CubeFace::PositiveY => Vector3::unit_y(),
+            CubeFace::NegativeY => -Vector3::unit_y(),
+            CubeFace::PositiveZ => Vector3::unit_z(),
+            CubeFace::NegativeZ => -Vector3::unit_z(),
+        }
+    }
+
+    /// The up vector for this face. Cubemap faces are stored with their
+    /// origin at the top left, so the side faces look "upside down" from the
+    /// point of view of a regular camera.
+    pub fn up(self) -> Vector3<f32> {
+        match self {
+            CubeFace::PositiveY => Vector3::unit_z(),
+            CubeFace::NegativeY => -Vector3::unit_z(),
+            _ => -Vector3::unit_y(),
+        }
+    }
+}
+
+/// A rig of six 90° cameras sharing a position, one per cubemap face, for
+/// rendering reflection probes, environment maps and point light shadows.
+///
+/// # Example
+///
+/// ```
+/// # use valor_camera::{CubeCamera, CubeFace};
+/// # use cgmath::Vector3;
+/// let probe = CubeCamera::new(Vector3::new(0.0, 1.0, 0.0)).with_far_plane(100.0);
+///
+/// for face in CubeFace::ALL.iter() {
+///     let view_proj: [[f32; 4]; 4] = probe.view_proj(*face).into();
+///     // Render the scene into the matching layer of a cubemap
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubeCamera {
+    /// The point the cubemap is captured from
+    pub position: Vector3<f32>,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl CubeCamera {
+    pub fn new(position: Vector3<f32>) -> Self {
+        CubeCamera {
+            position,
+            near: DEFAULT_NEAR,
+            far: DEFAULT_FAR,
+        }
+    }
+
+    pub fn with_near_plane(mut self, near: f32) -> Self {
+        self.near = near;
+        self
+    }
+
+    pub fn with_far_plane(mut self, far: f32) -> Self {
+        self.far = far;
+        self
+    }
+
+    /// The view matrix for `face`
+    pub fn view_matrix(&self, face: CubeFace) -> Matrix4<f32> {
+        let eye = Point3::new(self.position.x, self.position.y, self.position.z);
+        Matrix4::look_at_dir(eye, face.direction(), face.up())
+    }
+
+    /// The projection shared by every face: a square frustum with a 90° field
+    /// of view, so neighbouring faces meet exactly at their edges
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        let projection = Projection::Perspective {
+            fov: 90.0,
+            aspect_ratio: 1.0,
+            near: self.near,
+            far: self.far,
+        };
+        projection.matrix(1.0)
+    }
+
+    /// The combined view and projection for `face`
+    pub fn view_proj(&self, face: CubeFace) -> Matrix4<f32> {
+        self.projection_matrix() * self.view_matrix(face)
+    }
+
+    /// The view and projection of every face, in layer order
+    pub fn view_projs(&self) -> [Matrix4<f32>; 6] {
+        let face = |face: CubeFace| self.view_proj(face);
+        [
+            face(CubeFace::PositiveX),
+            face(CubeFace::NegativeX),
+            face(CubeFace::PositiveY),
+            face(CubeFace::NegativeY),
+            face(CubeFace::PositiveZ),
+            face(CubeFace::NegativeZ),
+        ]
+    }
+}
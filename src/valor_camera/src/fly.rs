@@ -0,0 +1,117 @@
+use crate::{Action, Camera, CameraController, CameraInput};
+use cgmath::prelude::*;
+use cgmath::Vector3;
+
+const DEFAULT_SPEED: f32 = 5.0;
+
+/// Which movement keys are currently held down.
+///
+/// The field names follow the usual WASD layout, with `up` and `down` for
+/// vertical movement.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FlyKeys {
+    pub forward: bool,
+    pub backward: bool,
+    pub left: bool,
+    pub right: bool,
+    pub up: bool,
+    pub down: bool,
+}
+
+/// Free-flying movement relative to the direction a `Camera` is facing, with
+/// pointer movement turning the camera through `Camera::mouse_look`.
+///
+/// # Example
+///
+/// ```
+/// # use valor_camera::{CameraBuilder, CameraController, FlyController};
+/// let mut camera = CameraBuilder::new().finish();
+/// let mut controller = FlyController::new();
+///
+/// controller.keys.forward = true;
+/// controller.update(1.0 / 60.0, &mut camera);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FlyController {
+    /// Keys held down since the last update
+    pub keys: FlyKeys,
+    /// Movement speed in units per second
+    pub speed: f32,
+    /// Pointer movement since the last update
+    look: (f32, f32),
+}
+
+impl Default for FlyController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlyController {
+    /// Create a controller with no keys held
+    pub fn new() -> Self {
+        FlyController {
+            keys: FlyKeys::default(),
+            speed: DEFAULT_SPEED,
+            look: (0.0, 0.0),
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+}
+
+impl CameraController for FlyController {
+    fn handle_input(&mut self, input: &CameraInput) {
+        match *input {
+            CameraInput::Action { action, active } => match action {
+                Action::Forward => self.keys.forward = active,
+                Action::Backward => self.keys.backward = active,
+                Action::Left => self.keys.left = active,
+                Action::Right => self.keys.right = active,
+                Action::Up => self.keys.up = active,
+                Action::Down => self.keys.down = active,
+                Action::Rotate | Action::Pan => {}
+            },
+            CameraInput::Look { dx, dy } => {
+                self.look.0 += dx;
+                self.look.1 += dy;
+            }
+            CameraInput::Zoom(_) => {}
+        }
+    }
+
+    /// Turn `camera` by the pointer movement received and move it according
+    /// to the keys held
+    fn update(&mut self, dt: f32, camera: &mut Camera) {
+        let (dx, dy) = self.look;
+        if dx != 0.0 || dy != 0.0 {
+            camera.mouse_look(dy, dx);
+            self.look = (0.0, 0.0);
+        }
+
+        let axis = |positive: bool, negative: bool| match (positive, negative) {
+            (true, false) => 1.0,
+            (false, true) => -1.0,
+            _ => 0.0,
+        };
+
+        let direction = Vector3::new(
+            axis(self.keys.right, self.keys.left),
+            axis(self.keys.up, self.keys.down),
+            axis(self.keys.forward, self.keys.backward),
+        );
+
+        // Moving diagonally should not be faster than along a single axis
+        if direction.magnitude2() == 0.0 {
+            return;
+        }
+        let direction = direction.normalize() * self.speed * dt;
+
+        camera.strafe_right(direction.x);
+        camera.move_up(direction.y);
+        camera.move_forward(direction.z);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::math::{decompose, exp_decay, slerp, spring};
+use crate::{Camera, CameraController, CameraInput};
+use cgmath::prelude::*;
+use cgmath::{Matrix4, Quaternion, Vector3};
+
+const DEFAULT_OFFSET: [f32; 3] = [0.0, 2.0, 6.0];
+const DEFAULT_LOOK_OFFSET: [f32; 3] = [0.0, 1.0, 0.0];
+const DEFAULT_SMOOTH_TIME: f32 = 0.3;
+const DEFAULT_ROTATION_LAG: f32 = 0.2;
+/// Fraction of the offset covered by each scroll step
+const ZOOM_SPEED: f32 = 0.1;
+
+/// Third-person camera which trails behind a moving target.
+///
+/// The camera is pulled towards `offset`, expressed in the target's local
+/// space, by a critically damped spring, and the target's orientation is
+/// followed with a lag so turns swing the camera around smoothly. Zoom input
+/// moves the camera closer to or further from the target.
+///
+/// # Example
+///
+/// ```
+/// # use valor_camera::{CameraBuilder, CameraController, FollowController};
+/// # use cgmath::{Quaternion, Vector3};
+/// # use cgmath::prelude::*;
+/// let mut camera = CameraBuilder::new().finish();
+/// let mut follow = FollowController::new();
+///
+/// follow.set_target(Vector3::new(0.0, 0.0, -10.0), Quaternion::one());
+/// follow.update(1.0 / 60.0, &mut camera);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FollowController {
+    target_position: Vector3<f32>,
+    target_orientation: Quaternion<f32>,
+    /// Where the camera sits relative to the target, in the target's space
+    pub offset: Vector3<f32>,
+    /// The point the camera looks at relative to the target, in the target's
+    /// space
+    pub look_offset: Vector3<f32>,
+    /// Roughly how many seconds the camera takes to catch up with the target
+    pub smooth_time: f32,
+    /// Time constant, in seconds, of the lag behind the target's rotation
+    pub rotation_lag: f32,
+    position: Vector3<f32>,
+    velocity: Vector3<f32>,
+    orientation: Quaternion<f32>,
+    /// Whether the camera should jump straight to the target on the next
+    /// update
+    snap: bool,
+}
+
+impl Default for FollowController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FollowController {
+    /// Create a controller which will snap to its target on the first update
+    pub fn new() -> Self {
+        FollowController {
+            target_position: Vector3::zero(),
+            target_orientation: Quaternion::one(),
+            offset: DEFAULT_OFFSET.into(),
+            look_offset: DEFAULT_LOOK_OFFSET.into(),
+            smooth_time: DEFAULT_SMOOTH_TIME,
+            rotation_lag: DEFAULT_ROTATION_LAG,
+            position: Vector3::zero(),
+            velocity: Vector3::zero(),
+            orientation: Quaternion::one(),
+            snap: true,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vector3<f32>) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_look_offset(mut self, look_offset: Vector3<f32>) -> Self {
+        self.look_offset = look_offset;
+        self
+    }
+
+    pub fn with_smooth_time(mut self, smooth_time: f32) -> Self {
+        self.smooth_time = smooth_time;
+        self
+    }
+
+    pub fn with_rotation_lag(mut self, rotation_lag: f32) -> Self {
+        self.rotation_lag = rotation_lag;
+        self
+    }
+
+    /// Update the position and orientation being followed
+    pub fn set_target(&mut self, position: Vector3<f32>, orientation: Quaternion<f32>) {
+        self.target_position = position;
+        self.target_orientation = orientation.normalize();
+    }
+
+    /// Follow a world transform, such as one produced by traversing a scene.
+    /// Any scale in the transform is ignored.
+    pub fn set_target_transform(&mut self, transform: Matrix4<f32>) {
+        let (position, orientation) = decompose(transform);
+        self.set_target(position, orientation);
+    }
+
+    /// Jump straight to the target on the next update instead of easing
+    /// towards it, e.g. after a teleport or cut
+    pub fn snap(&mut self) {
+        self.snap = true;
+    }
+}
+
+impl CameraController for FollowController {
+    fn handle_input(&mut self, input: &CameraInput) {
+        if let CameraInput::Zoom(steps) = *input {
+            self.offset *= (1.0 - ZOOM_SPEED).powf(steps);
+        }
+    }
+
+    /// Advance the smoothing by `dt` seconds and place `camera` accordingly
+    fn update(&mut self, dt: f32, camera: &mut Camera) {
+        if self.snap {
+            self.orientation = self.target_orientation;
+        } else {
+            let amount = exp_decay(self.rotation_lag, dt);
+            self.orientation = slerp(self.orientation, self.target_orientation, amount);
+        }
+
+        let desired = self.target_position + self.orientation * self.offset;
+
+        if self.snap {
+            self.position = desired;
+            self.velocity = Vector3::zero();
+            self.snap = false;
+        } else {
+            self.position = spring(
+                self.position,
+                desired,
+                &mut self.velocity,
+                self.smooth_time,
+                dt,
+            );
+        }
+
+        let up = self.orientation * Vector3::unit_y();
+        let look_target = self.target_position + self.orientation * self.look_offset;
+
+        camera.set_position(self.position);
+        camera.look_at(look_target, up);
+    }
+}
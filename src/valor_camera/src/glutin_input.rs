@@ -0,0 +1,157 @@
+use crate::{Action, CameraInput};
+use glutin::{
+    DeviceEvent, ElementState, Event, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode,
+    Window, WindowEvent,
+};
+use std::collections::HashMap;
+
+/// Pixels of smooth scrolling treated as one scroll step
+const PIXELS_PER_SCROLL_STEP: f32 = 20.0;
+
+/// Translates glutin events into `CameraInput` using configurable key and
+/// mouse button bindings.
+///
+/// Pointer movement is only reported while the cursor is grabbed or a bound
+/// mouse button is held, so a fly camera turns only once the cursor has been
+/// captured and an orbit camera only while dragging.
+///
+/// # Example
+///
+/// ```no_run
+/// # use valor_camera::{CameraBuilder, CameraController, FlyController, GlutinInput};
+/// # let mut events_loop = glutin::EventsLoop::new();
+/// let mut camera = CameraBuilder::new().finish();
+/// let mut controller = FlyController::new();
+/// let mut input = GlutinInput::new();
+///
+/// events_loop.poll_events(|event| {
+///     if let Some(input) = input.translate(&event) {
+///         controller.handle_input(&input);
+///     }
+/// });
+/// controller.update(1.0 / 60.0, &mut camera);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GlutinInput {
+    keys: HashMap<VirtualKeyCode, Action>,
+    buttons: HashMap<MouseButton, Action>,
+    buttons_held: usize,
+    grabbed: bool,
+}
+
+impl Default for GlutinInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlutinInput {
+    /// Create an adapter with WASD movement, space and left shift for up and
+    /// down, the left mouse button to rotate and the right to pan
+    pub fn new() -> Self {
+        let keys = vec![
+            (VirtualKeyCode::W, Action::Forward),
+            (VirtualKeyCode::S, Action::Backward),
+            (VirtualKeyCode::A, Action::Left),
+            (VirtualKeyCode::D, Action::Right),
+            (VirtualKeyCode::Space, Action::Up),
+            (VirtualKeyCode::LShift, Action::Down),
+        ];
+        let buttons = vec![
+            (MouseButton::Left, Action::Rotate),
+            (MouseButton::Right, Action::Pan),
+        ];
+
+        GlutinInput {
+            keys: keys.into_iter().collect(),
+            buttons: buttons.into_iter().collect(),
+            buttons_held: 0,
+            grabbed: false,
+        }
+    }
+
+    /// Bind `key` to `action`, replacing any previous binding for the key
+    pub fn bind_key(&mut self, key: VirtualKeyCode, action: Action) {
+        self.keys.insert(key, action);
+    }
+
+    /// Bind `button` to `action`, replacing any previous binding for the
+    /// button
+    pub fn bind_button(&mut self, button: MouseButton, action: Action) {
+        self.buttons.insert(button, action);
+    }
+
+    /// Translate a glutin event, returning `None` for events which do not
+    /// steer the camera
+    pub fn translate(&mut self, event: &Event) -> Option<CameraInput> {
+        match event {
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => {
+                if self.grabbed || self.buttons_held > 0 {
+                    Some(CameraInput::Look {
+                        dx: delta.0 as f32,
+                        dy: delta.1 as f32,
+                    })
+                } else {
+                    None
+                }
+            }
+            Event::WindowEvent { event, .. } => self.translate_window_event(event),
+            _ => None,
+        }
+    }
+
+    fn translate_window_event(&mut self, event: &WindowEvent) -> Option<CameraInput> {
+        match *event {
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        virtual_keycode: Some(key),
+                        state,
+                        ..
+                    },
+                ..
+            } => self.keys.get(&key).map(|&action| CameraInput::Action {
+                action,
+                active: state == ElementState::Pressed,
+            }),
+            WindowEvent::MouseInput { button, state, .. } => {
+                let action = *self.buttons.get(&button)?;
+                let active = state == ElementState::Pressed;
+
+                if active {
+                    self.buttons_held += 1;
+                } else {
+                    self.buttons_held = self.buttons_held.saturating_sub(1);
+                }
+
+                Some(CameraInput::Action { action, active })
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let steps = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y,
+                    MouseScrollDelta::PixelDelta(position) => {
+                        position.y as f32 / PIXELS_PER_SCROLL_STEP
+                    }
+                };
+                Some(CameraInput::Zoom(steps))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_grabbed(&self) -> bool {
+        self.grabbed
+    }
+
+    /// Capture or release the cursor. While captured the cursor is hidden and
+    /// confined to `window`, and all pointer movement is reported.
+    pub fn set_grabbed(&mut self, window: &Window, grabbed: bool) -> Result<(), String> {
+        window.grab_cursor(grabbed)?;
+        window.hide_cursor(grabbed);
+        self.grabbed = grabbed;
+        Ok(())
+    }
+}
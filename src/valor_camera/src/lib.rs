@@ -5,80 +5,575 @@
 //! Create a camera:
 //!
 //! ```
-//! use valor::camera::Camera;
+//! use valor_camera::CameraBuilder;
 //!
-//! let mut camera = Camera::new();
+//! let mut camera = CameraBuilder::new().finish();
 //! ```
 
 mod builder;
+mod cascade;
+mod controller;
+mod cube;
+mod fly;
+mod follow;
+#[cfg(feature = "glutin")]
+mod glutin_input;
+mod math;
+mod orbit;
+mod path;
+mod projection;
+mod shake;
+mod stereo;
+mod transition;
+mod viewport;
 
 pub use crate::builder::CameraBuilder;
-use cgmath::{perspective, Deg, Matrix4, Vector3};
+pub use crate::cascade::{cascade_splits, Cascade, ShadowCascades};
+pub use crate::controller::{Action, CameraController, CameraInput};
+pub use crate::cube::{CubeCamera, CubeFace};
+pub use crate::fly::{FlyController, FlyKeys};
+pub use crate::follow::FollowController;
+#[cfg(feature = "glutin")]
+pub use crate::glutin_input::GlutinInput;
+use crate::math::decompose;
+pub use crate::orbit::OrbitController;
+pub use crate::path::{CameraPath, PathCurve};
+pub use crate::projection::Projection;
+pub use crate::shake::Shake;
+pub use crate::stereo::{Eye, StereoCamera};
+pub use crate::transition::{CameraPose, CameraTransition, Easing};
+pub use crate::viewport::Viewport;
+use cgmath::prelude::*;
+use cgmath::{Deg, Matrix3, Matrix4, Quaternion, Vector2, Vector3, Vector4};
 
-/// Perspective camera with positioning controls.
+/// Perspective or orthographic camera with positioning controls.
+///
+/// The camera looks down its local -Z axis with +Y up, and its orientation is
+/// stored as a quaternion so it may be freely rotated in all three axes.
 ///
 /// # Example usage
 ///
 /// ```
-/// # use valor::camera::Camera;
-/// let mut camera = Camera::new();
+/// # use valor_camera::CameraBuilder;
+/// let mut camera = CameraBuilder::new().finish();
 ///
 /// let view_proj_matrix: [[f32; 4]; 4] = camera.get_view_proj().into();
 /// ```
 pub struct Camera {
     position: Vector3<f32>,
-    perspective: Matrix4<f32>,
-    pitch: f32,
-    yaw: f32,
+    projection: Projection,
+    zoom: f32,
+    orientation: Quaternion<f32>,
     sensitivity: f32,
+    reverse_z: bool,
+    clip_plane: Option<Vector4<f32>>,
+    shake: Shake,
 }
 
 impl Camera {
     /// Create a new camera with some default attributes
-    pub fn new(perspective: Matrix4<f32>, sensitivity: f32) -> Self {
+    pub fn new(projection: Projection, sensitivity: f32) -> Self {
         let position = Vector3::new(0.0, 0.0, 0.0);
 
         Camera {
             position,
-            perspective,
+            projection,
+            zoom: 1.0,
             sensitivity,
-            pitch: 0.0,
-            yaw: 0.0,
+            orientation: Quaternion::one(),
+            reverse_z: false,
+            clip_plane: None,
+            shake: Shake::new(),
         }
     }
 
     pub fn use_perspective(&mut self, fov: f32, aspect: f32, near: f32, far: f32) {
-        self.perspective = perspective(Deg(fov), aspect, near, far);
+        self.projection = Projection::Perspective {
+            fov,
+            aspect_ratio: aspect,
+            near,
+            far,
+        };
+    }
+
+    /// Switch to an orthographic projection with explicit view-space bounds
+    pub fn use_orthographic(
+        &mut self,
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) {
+        self.projection = Projection::Orthographic {
+            left,
+            right,
+            bottom,
+            top,
+            near,
+            far,
+        };
+    }
+
+    pub fn use_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    pub fn projection(&self) -> &Projection {
+        &self.projection
+    }
+
+    /// Map the near plane to depth `1.0` and the far plane to `0.0`, with
+    /// clip-space depth running from 0 to 1 rather than -1 to 1.
+    ///
+    /// With a floating point depth buffer this spreads depth precision evenly
+    /// over distance, so distant geometry stops z-fighting and the far plane
+    /// can be at infinity. Draw with valor's `DepthMode::Reversed`, which
+    /// provides the float depth buffer and switches OpenGL to the 0 to 1 clip
+    /// range where the driver supports it. Without that support depth still
+    /// sorts correctly, but precision is no better than without reverse-Z.
+    pub fn set_reverse_z(&mut self, reverse_z: bool) {
+        self.reverse_z = reverse_z;
+    }
+
+    pub fn reverse_z(&self) -> bool {
+        self.reverse_z
+    }
+
+    /// Change the aspect ratio of the projection, e.g. to match the viewport
+    /// being drawn into. Projections with explicit bounds are unaffected.
+    pub fn set_aspect_ratio(&mut self, new_aspect_ratio: f32) {
+        match self.projection {
+            Projection::Perspective {
+                ref mut aspect_ratio,
+                ..
+            }
+            | Projection::OrthographicHeight {
+                ref mut aspect_ratio,
+                ..
+            } => *aspect_ratio = new_aspect_ratio,
+            Projection::Frustum { .. } | Projection::Orthographic { .. } => {}
+        }
+    }
+
+    /// Replace the near plane with an arbitrary world-space plane, as needed
+    /// when rendering the reflection in a mirror or the view through a
+    /// portal so nothing behind the surface is drawn.
+    ///
+    /// The plane is given as `(a, b, c, d)` where `ax + by + cz + d = 0`, and
+    /// the half-space where `ax + by + cz + d >= 0` is kept. The camera
+    /// should be on the clipped side. Depth precision is reduced the further
+    /// the plane is from the usual near plane.
+    pub fn set_clip_plane(&mut self, plane: Option<Vector4<f32>>) {
+        self.clip_plane = plane;
+    }
+
+    pub fn clip_plane(&self) -> Option<Vector4<f32>> {
+        self.clip_plane
+    }
+
+    /// The vertical field of view in degrees, if the camera uses a
+    /// perspective projection
+    pub fn fov(&self) -> Option<f32> {
+        match self.projection {
+            Projection::Perspective { fov, .. } => Some(fov),
+            _ => None,
+        }
+    }
+
+    /// Change the vertical field of view of a perspective projection.
+    /// Orthographic projections are unaffected.
+    pub fn set_fov(&mut self, new_fov: f32) {
+        if let Projection::Perspective { ref mut fov, .. } = self.projection {
+            *fov = new_fov;
+        }
+    }
+
+    /// Set the magnification applied to the projection. A zoom of `2.0` makes
+    /// everything appear twice as large.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.max(f32::EPSILON);
+    }
+
+    /// Multiply the current zoom by `factor`
+    pub fn zoom_by(&mut self, factor: f32) {
+        self.set_zoom(self.zoom * factor);
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
     }
 
     pub fn use_sensitivity(&mut self, sensitivity: f32) {
         self.sensitivity = sensitivity;
     }
 
+    /// The camera's location in world space
+    pub fn position(&self) -> Vector3<f32> {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vector3<f32>) {
+        self.position = position;
+    }
+
+    /// Move the camera by `offset` in world space
+    pub fn translate(&mut self, offset: Vector3<f32>) {
+        self.position += offset;
+    }
+
+    /// Move the camera `distance` units in the direction it is looking
+    pub fn move_forward(&mut self, distance: f32) {
+        self.translate(self.forward() * distance);
+    }
+
+    /// Move the camera `distance` units to its right
+    pub fn strafe_right(&mut self, distance: f32) {
+        self.translate(self.right() * distance);
+    }
+
+    /// Move the camera `distance` units along its up vector
+    pub fn move_up(&mut self, distance: f32) {
+        self.translate(self.up() * distance);
+    }
+
+    /// The rotation from camera space into world space
+    pub fn orientation(&self) -> Quaternion<f32> {
+        self.orientation
+    }
+
+    pub fn set_orientation(&mut self, orientation: Quaternion<f32>) {
+        self.orientation = orientation.normalize();
+    }
+
+    /// Apply `rotation`, expressed in the camera's local space, on top of the
+    /// current orientation
+    pub fn rotate(&mut self, rotation: Quaternion<f32>) {
+        self.set_orientation(self.orientation * rotation);
+    }
+
+    /// Roll the camera `angle` degrees about its forward axis. Positive angles
+    /// tip the camera's up vector towards its left.
+    pub fn roll(&mut self, angle: f32) {
+        self.rotate(Quaternion::from_angle_z(Deg(angle)));
+    }
+
+    /// Place the camera with a world transform, such as that of a scene node
+    /// the camera is mounted on. The camera looks down the transform's -Z
+    /// axis, any scale is ignored, and projection settings are unaffected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use valor_camera::CameraBuilder;
+    /// # use cgmath::{Deg, Matrix4, Vector3};
+    /// let mut camera = CameraBuilder::new().finish();
+    ///
+    /// // e.g. the transform yielded for the camera's node by `Scene::traverse`
+    /// let rig = Matrix4::from_translation(Vector3::new(0.0, 2.0, 5.0))
+    ///     * Matrix4::from_angle_y(Deg(30.0));
+    ///
+    /// camera.set_world_transform(rig);
+    /// ```
+    pub fn set_world_transform(&mut self, transform: Matrix4<f32>) {
+        let (position, orientation) = decompose(transform);
+        self.position = position;
+        self.set_orientation(orientation);
+    }
+
+    /// Turn the camera to face `target`, keeping its up vector as close to
+    /// `up` as possible. Nothing happens if `target` is at the camera's
+    /// position or directly along `up`.
+    pub fn look_at(&mut self, target: Vector3<f32>, up: Vector3<f32>) {
+        let forward = target - self.position;
+        let right = forward.cross(up);
+
+        if forward.magnitude2() <= f32::EPSILON || right.magnitude2() <= f32::EPSILON {
+            return;
+        }
+
+        let forward = forward.normalize();
+        let right = right.normalize();
+        let up = right.cross(forward);
+
+        self.set_orientation(Matrix3::from_cols(right, up, -forward).into());
+    }
+
+    /// Unit vector pointing in the direction the camera is looking
+    pub fn forward(&self) -> Vector3<f32> {
+        self.orientation * -Vector3::unit_z()
+    }
+
+    /// Unit vector pointing to the camera's right
+    pub fn right(&self) -> Vector3<f32> {
+        self.orientation * Vector3::unit_x()
+    }
+
+    /// Unit vector pointing towards the top of the camera's view
+    pub fn up(&self) -> Vector3<f32> {
+        self.orientation * Vector3::unit_y()
+    }
+
+    /// Settings and trauma for camera shake
+    pub fn shake(&self) -> &Shake {
+        &self.shake
+    }
+
+    pub fn shake_mut(&mut self) -> &mut Shake {
+        &mut self.shake
+    }
+
+    /// Add trauma to shake the camera. The shake is layered over the view
+    /// matrix, leaving the position and orientation untouched.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.shake.add_trauma(amount);
+    }
+
+    /// Advance camera shake by `dt` seconds. Call this once per frame.
+    pub fn update_shake(&mut self, dt: f32) {
+        self.shake.update(dt);
+    }
+
+    /// The transformation from world space into camera space
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        let rotation = Matrix4::from(self.orientation.conjugate());
+
+        let translation = Matrix4::from_translation(-self.position);
+
+        let view = rotation * translation;
+
+        match self.shake.transform().and_then(|shake| shake.invert()) {
+            Some(shake) => shake * view,
+            None => view,
+        }
+    }
+
+    /// The transformation from camera space back into world space
+    pub fn inverse_view_matrix(&self) -> Matrix4<f32> {
+        let inverse = Matrix4::from_translation(self.position) * Matrix4::from(self.orientation);
+
+        match self.shake.transform() {
+            Some(shake) => inverse * shake,
+            None => inverse,
+        }
+    }
+
+    /// The world-space corners of the slice of the view volume between `near`
+    /// and `far` units in front of the camera. The four corners on the near
+    /// side come first, each side ordered bottom left, bottom right, top
+    /// right, top left.
+    ///
+    /// This is useful for fitting shadow maps or culling volumes to part of
+    /// what the camera can see.
+    pub fn frustum_corners(&self, near: f32, far: f32) -> [Vector3<f32>; 8] {
+        let inverse_view = self.inverse_view_matrix();
+        let near = self.projection.corners(self.zoom, near);
+        let far = self.projection.corners(self.zoom, far);
+
+        let mut corners = [Vector3::zero(); 8];
+        for (corner, view) in corners.iter_mut().zip(near.iter().chain(far.iter())) {
+            *corner = (inverse_view * view.extend(1.0)).truncate();
+        }
+        corners
+    }
+
+    /// The transformation from camera space into clip space
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        self.finish_projection(&self.projection, self.inverse_view_matrix())
+    }
+
+    /// Calculate the matrix for `projection` with the camera's zoom, oblique
+    /// clip plane and reverse-Z settings, for a view whose inverse is
+    /// `inverse_view`
+    fn finish_projection(
+        &self,
+        projection: &Projection,
+        inverse_view: Matrix4<f32>,
+    ) -> Matrix4<f32> {
+        match self.clip_plane {
+            Some(plane) => {
+                let matrix = oblique(
+                    projection.matrix(self.zoom),
+                    inverse_view.transpose() * plane,
+                );
+
+                // An oblique near plane already trades away depth precision,
+                // so remapping its depth range loses little more
+                if self.reverse_z {
+                    #[rustfmt::skip]
+                    let reverse = Matrix4::new(
+                        1.0, 0.0, 0.0,  0.0,
+                        0.0, 1.0, 0.0,  0.0,
+                        0.0, 0.0, -0.5, 0.0,
+                        0.0, 0.0, 0.5,  1.0,
+                    );
+                    reverse * matrix
+                } else {
+                    matrix
+                }
+            }
+            None if self.reverse_z => projection.reversed_matrix(self.zoom),
+            None => projection.matrix(self.zoom),
+        }
+    }
+
+    /// The transformation from clip space back into camera space, if the
+    /// projection is invertible
+    pub fn inverse_projection_matrix(&self) -> Option<Matrix4<f32>> {
+        self.projection_matrix().invert()
+    }
+
     /// Calculate the combined transformation matrix encoded by the `Camera`
     pub fn get_view_proj(&self) -> Matrix4<f32> {
-        let rotation =
-            Matrix4::from_angle_x(Deg(self.pitch)) * Matrix4::from_angle_y(Deg(self.yaw));
+        self.projection_matrix() * self.view_matrix()
+    }
 
-        let translation = Matrix4::from_translation(self.position);
+    /// The transformation from clip space back into world space, if the
+    /// projection is invertible
+    pub fn inverse_view_proj(&self) -> Option<Matrix4<f32>> {
+        self.inverse_projection_matrix()
+            .map(|inverse| self.inverse_view_matrix() * inverse)
+    }
 
-        self.perspective * rotation * translation
+    /// Find where `point` appears within `viewport`.
+    ///
+    /// The result holds the pixel position relative to the top-left of the
+    /// window in `x` and `y`, and the depth in the range `0.0` (near plane) to
+    /// `1.0` (far plane) in `z`, whether or not reverse-Z is in use. Returns
+    /// `None` for points behind the camera.
+    pub fn project(&self, point: Vector3<f32>, viewport: &Viewport) -> Option<Vector3<f32>> {
+        let clip = self.get_view_proj() * point.extend(1.0);
+
+        if clip.w <= 0.0 {
+            return None;
+        }
+
+        let ndc = clip.truncate() / clip.w;
+        let depth = if self.reverse_z {
+            1.0 - ndc.z
+        } else {
+            (ndc.z + 1.0) / 2.0
+        };
+
+        Some(Vector3::new(
+            viewport.x + (ndc.x + 1.0) / 2.0 * viewport.width,
+            viewport.y + (1.0 - ndc.y) / 2.0 * viewport.height,
+            depth,
+        ))
     }
 
-    /// Update the pitch and yaw attributes of the camera as a mouse moves
+    /// Find the world position which appears at `point` within `viewport`, at
+    /// the given `depth`. This is the inverse of `project`. Returns `None` if
+    /// the viewport has no area.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use valor_camera::{CameraBuilder, Viewport};
+    /// # use cgmath::{InnerSpace, Vector2, Vector3};
+    /// let camera = CameraBuilder::new().finish();
+    /// let viewport = Viewport::from_size(800.0, 600.0);
+    ///
+    /// let point = Vector3::new(1.0, 0.5, -4.0);
+    /// let screen = camera.project(point, &viewport).unwrap();
+    /// let world = camera
+    ///     .unproject(Vector2::new(screen.x, screen.y), screen.z, &viewport)
+    ///     .unwrap();
+    ///
+    /// assert!((world - point).magnitude() < 1e-3);
+    ///
+    /// let minimized = Viewport::from_size(0.0, 0.0);
+    /// assert!(camera.unproject(Vector2::new(0.0, 0.0), 0.5, &minimized).is_none());
+    /// ```
+    pub fn unproject(
+        &self,
+        point: Vector2<f32>,
+        depth: f32,
+        viewport: &Viewport,
+    ) -> Option<Vector3<f32>> {
+        if viewport.width <= 0.0 || viewport.height <= 0.0 {
+            return None;
+        }
+
+        let ndc = Vector4::new(
+            (point.x - viewport.x) / viewport.width * 2.0 - 1.0,
+            1.0 - (point.y - viewport.y) / viewport.height * 2.0,
+            if self.reverse_z {
+                1.0 - depth
+            } else {
+                depth * 2.0 - 1.0
+            },
+            1.0,
+        );
+
+        let world = self.inverse_view_proj()? * ndc;
+
+        if world.w == 0.0 {
+            None
+        } else {
+            Some(world.truncate() / world.w)
+        }
+    }
+
+    /// Cast a ray from the near plane through `point` within `viewport`,
+    /// returning its origin and unit direction, e.g. to pick what was clicked
+    pub fn screen_ray(
+        &self,
+        point: Vector2<f32>,
+        viewport: &Viewport,
+    ) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        // The far plane may be at infinity, so aim through a nearer depth
+        let near = self.unproject(point, 0.0, viewport)?;
+        let further = self.unproject(point, 0.5, viewport)?;
+
+        Some((near, (further - near).normalize()))
+    }
+
+    /// Update the pitch and yaw of the camera as a mouse moves
     /// `pitch_delta` and `yaw_delta` are pixel movement distances
+    ///
+    /// Yaw turns about the world Y axis and pitch about the camera's right
+    /// axis, as in a first-person game.
     pub fn mouse_look(&mut self, pitch_delta: f32, yaw_delta: f32) {
-        let mut pitch = self.pitch + (pitch_delta * self.sensitivity);
-        let yaw = self.yaw + (yaw_delta * self.sensitivity);
+        // Positive pitch looks down. Without roll, the up vector's height is
+        // the cosine of the pitch, which stays accurate near straight up and
+        // down where `asin` of the forward vector's height would drift
+        let pitch = (-self.forward().y).atan2(self.up().y).to_degrees();
+        let pitch_delta = pitch_delta * self.sensitivity;
+        let yaw_delta = yaw_delta * self.sensitivity;
 
         // Do not let the player look backwards up or down
-        if pitch < -90.0 {
-            pitch = -90.0
-        } else if pitch > 90.0 {
-            pitch = 90.0
-        }
+        let pitch_delta = (pitch + pitch_delta).clamp(-90.0, 90.0) - pitch;
 
-        self.pitch = pitch;
-        self.yaw = yaw;
+        self.set_orientation(
+            Quaternion::from_angle_y(Deg(-yaw_delta))
+                * self.orientation
+                * Quaternion::from_angle_x(Deg(-pitch_delta)),
+        );
     }
 }
+
+/// Modify `projection` so that its near plane becomes the view-space `plane`,
+/// using Eric Lengyel's oblique near-plane clipping
+fn oblique(mut projection: Matrix4<f32>, plane: Vector4<f32>) -> Matrix4<f32> {
+    let inverse = match projection.invert() {
+        Some(inverse) => inverse,
+        None => return projection,
+    };
+
+    // The corner of the view volume opposite the plane, in view space
+    let corner = inverse * Vector4::new(plane.x.signum(), plane.y.signum(), 1.0, 1.0);
+    let scale = plane.dot(corner);
+    if scale == 0.0 {
+        return projection;
+    }
+
+    let clip = plane * (2.0 / scale);
+    projection.x.z = clip.x - projection.x.w;
+    projection.y.z = clip.y - projection.y.w;
+    projection.z.z = clip.z - projection.z.w;
+    projection.w.z = clip.w - projection.w.w;
+
+    projection
+}
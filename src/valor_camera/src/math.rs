@@ -0,0 +1,57 @@
+use cgmath::prelude::*;
+use cgmath::{Matrix3, Matrix4, Quaternion, Vector3};
+
+/// Split a world transform into its translation and rotation, discarding any
+/// scale
+pub(crate) fn decompose(transform: Matrix4<f32>) -> (Vector3<f32>, Quaternion<f32>) {
+    let rotation = Matrix3::from_cols(
+        transform.x.truncate().normalize(),
+        transform.y.truncate().normalize(),
+        transform.z.truncate().normalize(),
+    );
+
+    (transform.w.truncate(), rotation.into())
+}
+
+/// Spherical interpolation which always takes the shortest path between two
+/// orientations, and stays well defined when they are nearly opposite.
+pub(crate) fn slerp(from: Quaternion<f32>, to: Quaternion<f32>, amount: f32) -> Quaternion<f32> {
+    let to = if from.dot(to) < 0.0 { -to } else { to };
+    from.slerp(to, amount).normalize()
+}
+
+/// Interpolation factor which moves a value `1 - e^-1` of the way to its goal
+/// every `time_constant` seconds, independent of frame rate
+pub(crate) fn exp_decay(time_constant: f32, dt: f32) -> f32 {
+    if time_constant <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-dt / time_constant).exp()
+    }
+}
+
+/// Advance a critically damped spring from `current` towards `target` over
+/// `dt` seconds, updating `velocity` in place.
+///
+/// `smooth_time` is roughly the time taken to reach the target. The exact
+/// solution is used, so the result does not depend on the frame rate.
+pub(crate) fn spring(
+    current: Vector3<f32>,
+    target: Vector3<f32>,
+    velocity: &mut Vector3<f32>,
+    smooth_time: f32,
+    dt: f32,
+) -> Vector3<f32> {
+    if smooth_time <= 0.0 {
+        *velocity = Vector3::zero();
+        return target;
+    }
+
+    let omega = 2.0 / smooth_time;
+    let change = current - target;
+    let temp = (*velocity + change * omega) * dt;
+    let decay = (-omega * dt).exp();
+
+    *velocity = (*velocity - temp * omega) * decay;
+    target + (change + temp) * decay
+}
@@ -0,0 +1,180 @@
+use crate::{Action, Camera, CameraController, CameraInput};
+use cgmath::prelude::*;
+use cgmath::{Deg, Quaternion, Vector3};
+
+const DEFAULT_DISTANCE: f32 = 5.0;
+const DEFAULT_MIN_DISTANCE: f32 = 0.5;
+const DEFAULT_MAX_DISTANCE: f32 = 100.0;
+const DEFAULT_ROTATE_SPEED: f32 = 0.25;
+const DEFAULT_ZOOM_SPEED: f32 = 0.1;
+const DEFAULT_PAN_SPEED: f32 = 0.002;
+const MAX_ELEVATION: f32 = 89.0;
+
+/// Keeps a `Camera` circling and facing a target point, as in a model viewer.
+///
+/// As a `CameraController`, pointer movement rotates while `Action::Rotate` is
+/// held and pans while `Action::Pan` is held, and zoom input moves towards the
+/// target.
+///
+/// Azimuth is measured in degrees around the world Y axis, starting from +Z,
+/// and elevation in degrees above the horizontal plane through the target.
+///
+/// # Example
+///
+/// ```
+/// # use valor_camera::{CameraBuilder, OrbitController};
+/// # use cgmath::Vector3;
+/// let mut camera = CameraBuilder::new().finish();
+/// let mut orbit = OrbitController::new(Vector3::new(0.0, 1.0, 0.0));
+///
+/// // Drag the mouse 40 pixels right, then scroll in
+/// orbit.rotate(40.0, 0.0);
+/// orbit.zoom(1.0);
+/// orbit.apply(&mut camera);
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrbitController {
+    target: Vector3<f32>,
+    distance: f32,
+    azimuth: f32,
+    elevation: f32,
+    min_distance: f32,
+    max_distance: f32,
+    /// Degrees of rotation per pixel dragged
+    pub rotate_speed: f32,
+    /// Fraction of the distance covered by each scroll step
+    pub zoom_speed: f32,
+    /// Fraction of the distance the target moves per pixel panned
+    pub pan_speed: f32,
+    rotating: bool,
+    panning: bool,
+}
+
+impl OrbitController {
+    /// Create a controller orbiting `target` from directly in front of it
+    pub fn new(target: Vector3<f32>) -> Self {
+        OrbitController {
+            target,
+            distance: DEFAULT_DISTANCE,
+            azimuth: 0.0,
+            elevation: 0.0,
+            min_distance: DEFAULT_MIN_DISTANCE,
+            max_distance: DEFAULT_MAX_DISTANCE,
+            rotate_speed: DEFAULT_ROTATE_SPEED,
+            zoom_speed: DEFAULT_ZOOM_SPEED,
+            pan_speed: DEFAULT_PAN_SPEED,
+            rotating: false,
+            panning: false,
+        }
+    }
+
+    /// Limit how close to and far from the target the camera may be
+    pub fn with_distance_limits(mut self, min_distance: f32, max_distance: f32) -> Self {
+        self.min_distance = min_distance;
+        self.max_distance = max_distance.max(min_distance);
+        self.set_distance(self.distance);
+        self
+    }
+
+    pub fn target(&self) -> Vector3<f32> {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: Vector3<f32>) {
+        self.target = target;
+    }
+
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    pub fn set_distance(&mut self, distance: f32) {
+        self.distance = distance.clamp(self.min_distance, self.max_distance);
+    }
+
+    pub fn azimuth(&self) -> f32 {
+        self.azimuth
+    }
+
+    pub fn elevation(&self) -> f32 {
+        self.elevation
+    }
+
+    /// Set the azimuth and elevation, in degrees
+    pub fn set_angles(&mut self, azimuth: f32, elevation: f32) {
+        self.azimuth = azimuth % 360.0;
+        self.elevation = elevation.clamp(-MAX_ELEVATION, MAX_ELEVATION);
+    }
+
+    /// Rotate around the target as the mouse is dragged. `dx` and `dy` are
+    /// pixel movement distances.
+    pub fn rotate(&mut self, dx: f32, dy: f32) {
+        self.set_angles(
+            self.azimuth - dx * self.rotate_speed,
+            self.elevation + dy * self.rotate_speed,
+        );
+    }
+
+    /// Move towards the target for positive scroll `steps` and away from it
+    /// for negative ones
+    pub fn zoom(&mut self, steps: f32) {
+        self.set_distance(self.distance * (1.0 - self.zoom_speed).powf(steps));
+    }
+
+    /// Move the target within the view plane as the mouse is dragged, so the
+    /// scene follows the cursor. `dx` and `dy` are pixel movement distances.
+    ///
+    /// Panning is scaled by the distance to the target, so it feels the same
+    /// however far the camera is zoomed out.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let rotation = self.rotation();
+        let right = rotation * Vector3::unit_x();
+        let up = rotation * Vector3::unit_y();
+
+        self.target += (right * -dx + up * dy) * self.distance * self.pan_speed;
+    }
+
+    /// The orientation of a camera at the orbit position facing the target
+    fn rotation(&self) -> Quaternion<f32> {
+        Quaternion::from_angle_y(Deg(self.azimuth)) * Quaternion::from_angle_x(Deg(-self.elevation))
+    }
+
+    /// Where a camera orbiting the target should be placed
+    pub fn eye(&self) -> Vector3<f32> {
+        self.target + self.rotation() * Vector3::unit_z() * self.distance
+    }
+
+    /// Position and orient `camera` to look at the target from the orbit
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.set_position(self.eye());
+        camera.set_orientation(self.rotation());
+    }
+}
+
+impl CameraController for OrbitController {
+    fn handle_input(&mut self, input: &CameraInput) {
+        match *input {
+            CameraInput::Action {
+                action: Action::Rotate,
+                active,
+            } => self.rotating = active,
+            CameraInput::Action {
+                action: Action::Pan,
+                active,
+            } => self.panning = active,
+            CameraInput::Action { .. } => {}
+            CameraInput::Look { dx, dy } => {
+                if self.rotating {
+                    self.rotate(dx, dy);
+                } else if self.panning {
+                    self.pan(dx, dy);
+                }
+            }
+            CameraInput::Zoom(steps) => self.zoom(steps),
+        }
+    }
+
+    fn update(&mut self, _dt: f32, camera: &mut Camera) {
+        self.apply(camera);
+    }
+}
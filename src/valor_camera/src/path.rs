@@ -0,0 +1,252 @@
+use crate::math::slerp;
+use crate::Camera;
+use cgmath::prelude::*;
+use cgmath::{Quaternion, Vector3};
+
+/// Number of straight pieces each curve segment is split into when measuring
+/// its length
+const SAMPLES_PER_SEGMENT: usize = 32;
+
+/// The kind of spline a `CameraPath` follows through its control points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCurve {
+    /// Passes through every control point
+    CatmullRom,
+    /// Piecewise cubic Bézier curve. Points are given as
+    /// `[start, control, control, end, control, control, end, ...]`, so each
+    /// segment needs exactly three more points after the start.
+    Bezier,
+}
+
+/// A spline for a camera to fly along, sampled by distance travelled rather
+/// than by curve parameter so that motion along it has constant speed.
+///
+/// # Example
+///
+/// ```
+/// # use valor_camera::{CameraBuilder, CameraPath};
+/// # use cgmath::Vector3;
+/// let mut camera = CameraBuilder::new().finish();
+/// let path = CameraPath::catmull_rom(vec![
+///     Vector3::new(0.0, 1.0, 0.0),
+///     Vector3::new(5.0, 2.0, -5.0),
+///     Vector3::new(10.0, 1.0, 0.0),
+/// ])
+/// .unwrap();
+///
+/// // Halfway along the path, by distance
+/// path.apply(0.5, &mut camera);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CameraPath {
+    curve: PathCurve,
+    /// Enough points for at least one whole segment of `curve`
+    points: Vec<Vector3<f32>>,
+    /// Orientations at fractions of the path's length, sorted by fraction
+    keyframes: Vec<(f32, Quaternion<f32>)>,
+    /// Cumulative length at each evenly spaced curve parameter
+    lengths: Vec<f32>,
+}
+
+impl CameraPath {
+    /// Create a path from control points and measure its length.
+    ///
+    /// Returns `None` unless the points form one or more whole segments of
+    /// `curve`: at least two points for a Catmull-Rom spline, and one more
+    /// than a multiple of three, but at least four, for a Bézier curve.
+    pub fn new(curve: PathCurve, points: Vec<Vector3<f32>>) -> Option<Self> {
+        let whole_segments = match curve {
+            PathCurve::CatmullRom => points.len() >= 2,
+            PathCurve::Bezier => points.len() >= 4 && (points.len() - 1).is_multiple_of(3),
+        };
+        if whole_segments {
+            Some(CameraPath::measured(curve, points))
+        } else {
+            None
+        }
+    }
+
+    /// Create a Catmull-Rom spline passing through `points`, or `None` if
+    /// there are fewer than two
+    pub fn catmull_rom(points: Vec<Vector3<f32>>) -> Option<Self> {
+        CameraPath::new(PathCurve::CatmullRom, points)
+    }
+
+    /// Create a piecewise cubic Bézier curve from `points`, or `None` if
+    /// their number isn't one more than a multiple of three, or is less than
+    /// four
+    pub fn bezier(points: Vec<Vector3<f32>>) -> Option<Self> {
+        CameraPath::new(PathCurve::Bezier, points)
+    }
+
+    /// Face the camera towards `orientation` when it is `at` (a fraction
+    /// from `0.0` to `1.0`) of the way along the path. Between keyframes the
+    /// orientation is interpolated.
+    ///
+    /// A path with no keyframes faces the camera along the direction of
+    /// travel.
+    pub fn with_orientation_keyframe(mut self, at: f32, orientation: Quaternion<f32>) -> Self {
+        let at = at.clamp(0.0, 1.0);
+        let index = self
+            .keyframes
+            .iter()
+            .position(|&(other, _)| other > at)
+            .unwrap_or(self.keyframes.len());
+        self.keyframes.insert(index, (at, orientation.normalize()));
+        self
+    }
+
+    /// Total length of the path
+    pub fn length(&self) -> f32 {
+        self.lengths.last().cloned().unwrap_or(0.0)
+    }
+
+    fn segments(&self) -> usize {
+        match self.curve {
+            PathCurve::CatmullRom => self.points.len().saturating_sub(1),
+            PathCurve::Bezier => self.points.len().saturating_sub(1) / 3,
+        }
+    }
+
+    /// Evaluate the curve at parameter `s`, from `0.0` at the start of the
+    /// first segment to the number of segments at the end of the last
+    fn evaluate(&self, s: f32) -> Vector3<f32> {
+        let segments = self.segments();
+        let s = s.clamp(0.0, segments as f32);
+        let segment = (s.floor() as usize).min(segments - 1);
+        let u = s - segment as f32;
+
+        match self.curve {
+            PathCurve::CatmullRom => {
+                let point = |i: isize| {
+                    let i = i.clamp(0, self.points.len() as isize - 1);
+                    self.points[i as usize]
+                };
+                let i = segment as isize;
+                let (p0, p1, p2, p3) = (point(i - 1), point(i), point(i + 1), point(i + 2));
+
+                (p1 * 2.0
+                    + (p2 - p0) * u
+                    + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * (u * u)
+                    + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * (u * u * u))
+                    * 0.5
+            }
+            PathCurve::Bezier => {
+                let i = segment * 3;
+                let (p0, p1, p2, p3) = (
+                    self.points[i],
+                    self.points[i + 1],
+                    self.points[i + 2],
+                    self.points[i + 3],
+                );
+                let v = 1.0 - u;
+
+                p0 * (v * v * v)
+                    + p1 * (3.0 * v * v * u)
+                    + p2 * (3.0 * v * u * u)
+                    + p3 * (u * u * u)
+            }
+        }
+    }
+
+    /// Create a path from points already known to form whole segments
+    fn measured(curve: PathCurve, points: Vec<Vector3<f32>>) -> Self {
+        let mut path = CameraPath {
+            curve,
+            points,
+            keyframes: Vec::new(),
+            lengths: Vec::new(),
+        };
+        path.measure();
+        path
+    }
+
+    /// Build the table mapping curve parameter to distance travelled
+    fn measure(&mut self) {
+        let samples = self.segments() * SAMPLES_PER_SEGMENT;
+        let mut lengths = Vec::with_capacity(samples + 1);
+        let mut total = 0.0;
+        let mut previous = self.evaluate(0.0);
+        lengths.push(total);
+
+        for sample in 1..=samples {
+            let point = self.evaluate(sample as f32 / SAMPLES_PER_SEGMENT as f32);
+            total += (point - previous).magnitude();
+            lengths.push(total);
+            previous = point;
+        }
+
+        self.lengths = lengths;
+    }
+
+    /// Curve parameter at a fraction `t` of the path's length
+    fn parameter(&self, t: f32) -> f32 {
+        let length = self.length();
+        if length <= 0.0 {
+            return 0.0;
+        }
+
+        let distance = t.clamp(0.0, 1.0) * length;
+        let upper = self
+            .lengths
+            .iter()
+            .position(|&l| l >= distance)
+            .unwrap_or(self.lengths.len() - 1)
+            .max(1);
+        let (start, end) = (self.lengths[upper - 1], self.lengths[upper]);
+        let fraction = if end > start {
+            (distance - start) / (end - start)
+        } else {
+            0.0
+        };
+
+        (upper as f32 - 1.0 + fraction) / SAMPLES_PER_SEGMENT as f32
+    }
+
+    /// Position a fraction `t` of the way along the path by distance
+    pub fn position_at(&self, t: f32) -> Vector3<f32> {
+        self.evaluate(self.parameter(t))
+    }
+
+    /// Direction of travel a fraction `t` of the way along the path, or
+    /// `None` if the path has no length
+    pub fn direction_at(&self, t: f32) -> Option<Vector3<f32>> {
+        let step = 1.0 / (self.lengths.len().max(2) - 1) as f32;
+        let (before, after) = ((t - step).max(0.0), (t + step).min(1.0));
+        let direction = self.position_at(after) - self.position_at(before);
+
+        if direction.magnitude2() > 0.0 {
+            Some(direction.normalize())
+        } else {
+            None
+        }
+    }
+
+    /// Orientation from the keyframes a fraction `t` of the way along the
+    /// path, or `None` if there are no keyframes
+    pub fn orientation_at(&self, t: f32) -> Option<Quaternion<f32>> {
+        let next = self.keyframes.iter().position(|&(at, _)| at > t);
+
+        match next {
+            Some(0) => self.keyframes.first().map(|&(_, q)| q),
+            Some(next) => {
+                let (start, from) = self.keyframes[next - 1];
+                let (end, to) = self.keyframes[next];
+                Some(slerp(from, to, (t - start) / (end - start)))
+            }
+            None => self.keyframes.last().map(|&(_, q)| q),
+        }
+    }
+
+    /// Place `camera` a fraction `t` of the way along the path
+    pub fn apply(&self, t: f32, camera: &mut Camera) {
+        let position = self.position_at(t);
+        camera.set_position(position);
+
+        if let Some(orientation) = self.orientation_at(t) {
+            camera.set_orientation(orientation);
+        } else if let Some(direction) = self.direction_at(t) {
+            camera.look_at(position + direction, Vector3::unit_y());
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use cgmath::{frustum, ortho, perspective, Deg, Matrix4, Rad, Vector3};
+
+/// The projection a `Camera` uses to map view space onto the screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// Perspective projection with a vertical field of view in degrees.
+    ///
+    /// `far` may be `f32::INFINITY` for a projection with no far plane.
+    Perspective {
+        fov: f32,
+        aspect_ratio: f32,
+        near: f32,
+        far: f32,
+    },
+    /// Perspective projection with explicit, possibly off-center, bounds on
+    /// the near plane, as used for portals and stereo rendering.
+    ///
+    /// `far` may be `f32::INFINITY` for a projection with no far plane.
+    Frustum {
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    },
+    /// Orthographic projection with explicit view-space bounds.
+    ///
+    /// `far` must be finite.
+    Orthographic {
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    },
+    /// Orthographic projection centered on the view axis, `height` units tall
+    /// and as wide as the aspect ratio requires.
+    ///
+    /// `far` must be finite.
+    OrthographicHeight {
+        height: f32,
+        aspect_ratio: f32,
+        near: f32,
+        far: f32,
+    },
+}
+
+impl Projection {
+    /// Distance to the near clipping plane
+    pub fn near(&self) -> f32 {
+        match *self {
+            Projection::Perspective { near, .. }
+            | Projection::Frustum { near, .. }
+            | Projection::Orthographic { near, .. }
+            | Projection::OrthographicHeight { near, .. } => near,
+        }
+    }
+
+    /// Distance to the far clipping plane, which may be infinite
+    pub fn far(&self) -> f32 {
+        match *self {
+            Projection::Perspective { far, .. }
+            | Projection::Frustum { far, .. }
+            | Projection::Orthographic { far, .. }
+            | Projection::OrthographicHeight { far, .. } => far,
+        }
+    }
+
+    /// The view-space corners of the cross-section of the view volume
+    /// `distance` units in front of the camera, magnified by `zoom`, in the
+    /// order bottom left, bottom right, top right, top left.
+    pub fn corners(&self, zoom: f32, distance: f32) -> [Vector3<f32>; 4] {
+        let (left, right, bottom, top) = match *self {
+            Projection::Perspective {
+                fov, aspect_ratio, ..
+            } => {
+                let top = distance * (fov.to_radians() / 2.0).tan() / zoom;
+                let right = top * aspect_ratio;
+                (-right, right, -top, top)
+            }
+            Projection::Frustum {
+                left,
+                right,
+                bottom,
+                top,
+                near,
+                ..
+            } => {
+                let scale = distance / (near * zoom);
+                (left * scale, right * scale, bottom * scale, top * scale)
+            }
+            Projection::Orthographic {
+                left,
+                right,
+                bottom,
+                top,
+                ..
+            } => {
+                let center_x = (left + right) / 2.0;
+                let center_y = (bottom + top) / 2.0;
+                let half_width = (right - left) / (2.0 * zoom);
+                let half_height = (top - bottom) / (2.0 * zoom);
+                (
+                    center_x - half_width,
+                    center_x + half_width,
+                    center_y - half_height,
+                    center_y + half_height,
+                )
+            }
+            Projection::OrthographicHeight {
+                height,
+                aspect_ratio,
+                ..
+            } => {
+                let half_height = height / (2.0 * zoom);
+                let half_width = half_height * aspect_ratio;
+                (-half_width, half_width, -half_height, half_height)
+            }
+        };
+
+        [
+            Vector3::new(left, bottom, -distance),
+            Vector3::new(right, bottom, -distance),
+            Vector3::new(right, top, -distance),
+            Vector3::new(left, top, -distance),
+        ]
+    }
+
+    /// Calculate the projection matrix, magnified by `zoom`.
+    ///
+    /// Zooming narrows the field of view of a perspective projection and
+    /// shrinks the bounds of an orthographic one. Frustum bounds shrink
+    /// towards the view axis.
+    pub fn matrix(&self, zoom: f32) -> Matrix4<f32> {
+        match *self {
+            Projection::Perspective {
+                fov,
+                aspect_ratio,
+                near,
+                far,
+            } => {
+                let half_fov: Rad<f32> = Deg(fov / 2.0).into();
+                let fov = Rad((half_fov.0.tan() / zoom).atan() * 2.0);
+
+                if far.is_infinite() {
+                    let top = near * (fov.0 / 2.0).tan();
+                    let right = top * aspect_ratio;
+                    infinite_frustum(-right, right, -top, top, near)
+                } else {
+                    perspective(fov, aspect_ratio, near, far)
+                }
+            }
+            Projection::Frustum {
+                left,
+                right,
+                bottom,
+                top,
+                near,
+                far,
+            } => {
+                let (left, right) = (left / zoom, right / zoom);
+                let (bottom, top) = (bottom / zoom, top / zoom);
+
+                if far.is_infinite() {
+                    infinite_frustum(left, right, bottom, top, near)
+                } else {
+                    frustum(left, right, bottom, top, near, far)
+                }
+            }
+            Projection::Orthographic {
+                left,
+                right,
+                bottom,
+                top,
+                near,
+                far,
+            } => {
+                let center_x = (left + right) / 2.0;
+                let center_y = (bottom + top) / 2.0;
+                let half_width = (right - left) / (2.0 * zoom);
+                let half_height = (top - bottom) / (2.0 * zoom);
+                ortho(
+                    center_x - half_width,
+                    center_x + half_width,
+                    center_y - half_height,
+                    center_y + half_height,
+                    near,
+                    far,
+                )
+            }
+            Projection::OrthographicHeight {
+                height,
+                aspect_ratio,
+                near,
+                far,
+            } => {
+                let half_height = height / (2.0 * zoom);
+                let half_width = half_height * aspect_ratio;
+                ortho(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    near,
+                    far,
+                )
+            }
+        }
+    }
+
+    /// Calculate the reverse-Z projection matrix, magnified by `zoom`.
+    ///
+    /// Clip-space depth runs from `1.0` at the near plane to `0.0` at the far
+    /// plane, or at infinity, instead of from `-1.0` to `1.0` as with
+    /// `matrix`. The depth row is calculated directly rather than by
+    /// remapping `matrix`, which would cancel away the precision reverse-Z is
+    /// used for.
+    pub fn reversed_matrix(&self, zoom: f32) -> Matrix4<f32> {
+        let (near, far) = (self.near(), self.far());
+        let (scale, offset) = match *self {
+            Projection::Perspective { .. } | Projection::Frustum { .. } => {
+                if far.is_infinite() {
+                    (0.0, near)
+                } else {
+                    (near / (far - near), far * near / (far - near))
+                }
+            }
+            Projection::Orthographic { .. } | Projection::OrthographicHeight { .. } => {
+                (1.0 / (far - near), far / (far - near))
+            }
+        };
+
+        // Only the depth row differs, and it depends on view-space depth alone
+        let mut matrix = self.matrix(zoom);
+        matrix.x.z = 0.0;
+        matrix.y.z = 0.0;
+        matrix.z.z = scale;
+        matrix.w.z = offset;
+        matrix
+    }
+}
+
+/// The limit of `cgmath::frustum` as the far plane tends to infinity
+fn infinite_frustum(left: f32, right: f32, bottom: f32, top: f32, near: f32) -> Matrix4<f32> {
+    let width = right - left;
+    let height = top - bottom;
+
+    #[rustfmt::skip]
+    let matrix = Matrix4::new(
+        2.0 * near / width,     0.0,                     0.0,         0.0,
+        0.0,                    2.0 * near / height,     0.0,         0.0,
+        (right + left) / width, (top + bottom) / height, -1.0,        -1.0,
+        0.0,                    0.0,                     -2.0 * near, 0.0,
+    );
+
+    matrix
+}
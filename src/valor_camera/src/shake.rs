@@ -0,0 +1,116 @@
+use cgmath::prelude::*;
+use cgmath::{Deg, Matrix4, Quaternion, Vector3};
+
+const DEFAULT_DECAY: f32 = 1.0;
+const DEFAULT_MAX_OFFSET: f32 = 0.3;
+const DEFAULT_MAX_ANGLE: f32 = 5.0;
+const DEFAULT_FREQUENCY: f32 = 15.0;
+/// Number of noise samples before the shake repeats itself
+const NOISE_PERIOD: i32 = 1024;
+
+/// Trauma-based camera shake.
+///
+/// Trauma is a value from `0.0` to `1.0` which is raised by events such as
+/// explosions and falls back to zero over time. The shake strength grows with
+/// the square of the trauma, so small knocks are subtle and large ones are
+/// violent. Offsets come from smooth noise rather than random jitter.
+#[derive(Debug, Clone)]
+pub struct Shake {
+    trauma: f32,
+    /// Position in the noise, in samples, kept below `NOISE_PERIOD` so it
+    /// doesn't lose precision however long the shake runs
+    phase: f32,
+    /// Trauma lost per second
+    pub decay: f32,
+    /// Largest displacement along each of the camera's local axes at full
+    /// trauma
+    pub max_offset: Vector3<f32>,
+    /// Largest pitch, yaw and roll in degrees at full trauma
+    pub max_angle: Vector3<f32>,
+    /// How quickly the shake changes direction, in noise samples per second
+    pub frequency: f32,
+}
+
+impl Default for Shake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shake {
+    /// Create a shake with no trauma
+    pub fn new() -> Self {
+        Shake {
+            trauma: 0.0,
+            phase: 0.0,
+            decay: DEFAULT_DECAY,
+            max_offset: Vector3::new(DEFAULT_MAX_OFFSET, DEFAULT_MAX_OFFSET, 0.0),
+            max_angle: Vector3::new(DEFAULT_MAX_ANGLE, DEFAULT_MAX_ANGLE, DEFAULT_MAX_ANGLE),
+            frequency: DEFAULT_FREQUENCY,
+        }
+    }
+
+    /// The current trauma, from `0.0` for a steady camera to `1.0` for the
+    /// strongest shake
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Increase the trauma, saturating at `1.0`
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Advance the noise and let trauma decay over `dt` seconds
+    pub fn update(&mut self, dt: f32) {
+        self.phase = (self.phase + dt * self.frequency).rem_euclid(NOISE_PERIOD as f32);
+        self.trauma = (self.trauma - self.decay * dt).max(0.0);
+    }
+
+    /// Transformation from the shaken camera into the steady camera's space,
+    /// or `None` while there is no trauma
+    pub(crate) fn transform(&self) -> Option<Matrix4<f32>> {
+        if self.trauma <= 0.0 {
+            return None;
+        }
+
+        let intensity = self.trauma * self.trauma;
+        let channel = |seed: u32| noise(seed, self.phase) * intensity;
+
+        let offset = Vector3::new(
+            self.max_offset.x * channel(0),
+            self.max_offset.y * channel(1),
+            self.max_offset.z * channel(2),
+        );
+        let rotation = Quaternion::from_angle_x(Deg(self.max_angle.x * channel(3)))
+            * Quaternion::from_angle_y(Deg(self.max_angle.y * channel(4)))
+            * Quaternion::from_angle_z(Deg(self.max_angle.z * channel(5)));
+
+        Some(Matrix4::from_translation(offset) * Matrix4::from(rotation))
+    }
+}
+
+/// Pseudo-random value from `-1.0` to `1.0` for an integer lattice point
+fn hash(seed: u32, n: i32) -> f32 {
+    let mut x = (n as u32).wrapping_mul(0x27d4_eb2d) ^ seed.wrapping_mul(0x1656_67b1);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x2c1b_3c6d);
+    x ^= x >> 12;
+    x = x.wrapping_mul(0x297a_2d39);
+    x ^= x >> 15;
+
+    (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+/// Smooth one-dimensional value noise from `-1.0` to `1.0`, repeating every
+/// `NOISE_PERIOD` samples
+fn noise(seed: u32, t: f32) -> f32 {
+    let floor = t.floor();
+    let fraction = t - floor;
+    let eased = fraction * fraction * (3.0 - 2.0 * fraction);
+
+    let a = hash(seed, (floor as i32).rem_euclid(NOISE_PERIOD));
+    let b = hash(seed, (floor as i32 + 1).rem_euclid(NOISE_PERIOD));
+
+    a + (b - a) * eased
+}
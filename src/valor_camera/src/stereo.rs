@@ -0,0 +1,122 @@
+use crate::{Camera, Projection};
+use cgmath::{Matrix4, Vector3};
+
+const DEFAULT_IPD: f32 = 0.064;
+const DEFAULT_CONVERGENCE: f32 = 10.0;
+
+/// One of the two eyes of a `StereoCamera`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eye {
+    Left,
+    Right,
+}
+
+/// A pair of views for side-by-side or headset stereo output, derived from a
+/// single `Camera`.
+///
+/// Each eye is offset by half the interpupillary distance along the camera's
+/// right axis, and uses an off-axis frustum so that both views line up at the
+/// convergence distance. This avoids the vertical parallax that toeing the
+/// eyes inwards would cause.
+///
+/// # Example
+///
+/// ```
+/// # use valor_camera::{CameraBuilder, StereoCamera};
+/// let camera = CameraBuilder::new().finish();
+/// let stereo = StereoCamera::new(camera).with_convergence(5.0);
+///
+/// let left: [[f32; 4]; 4] = stereo.left_view_proj().into();
+/// let right: [[f32; 4]; 4] = stereo.right_view_proj().into();
+/// ```
+pub struct StereoCamera {
+    /// The camera at the midpoint between the eyes
+    pub camera: Camera,
+    /// Distance between the eyes, in world units
+    pub ipd: f32,
+    /// Distance in front of the camera at which the two views coincide
+    pub convergence: f32,
+}
+
+impl StereoCamera {
+    /// Create a stereo pair with an average human interpupillary distance
+    pub fn new(camera: Camera) -> Self {
+        StereoCamera {
+            camera,
+            ipd: DEFAULT_IPD,
+            convergence: DEFAULT_CONVERGENCE,
+        }
+    }
+
+    pub fn with_ipd(mut self, ipd: f32) -> Self {
+        self.ipd = ipd;
+        self
+    }
+
+    pub fn with_convergence(mut self, convergence: f32) -> Self {
+        self.convergence = convergence;
+        self
+    }
+
+    /// Offset of `eye` from the camera along its right axis
+    fn offset(&self, eye: Eye) -> f32 {
+        match eye {
+            Eye::Left => -self.ipd / 2.0,
+            Eye::Right => self.ipd / 2.0,
+        }
+    }
+
+    /// The view matrix for `eye`
+    pub fn view_matrix(&self, eye: Eye) -> Matrix4<f32> {
+        let offset = Vector3::new(-self.offset(eye), 0.0, 0.0);
+        Matrix4::from_translation(offset) * self.camera.view_matrix()
+    }
+
+    /// The projection matrix for `eye`. Cameras without a perspective
+    /// projection use the same projection for both eyes.
+    pub fn projection_matrix(&self, eye: Eye) -> Matrix4<f32> {
+        let camera = &self.camera;
+        let offset = Vector3::new(self.offset(eye), 0.0, 0.0);
+        let inverse_view = camera.inverse_view_matrix() * Matrix4::from_translation(offset);
+
+        let projection = match camera.projection {
+            Projection::Perspective {
+                fov,
+                aspect_ratio,
+                near,
+                far,
+            } => {
+                let top = near * (fov.to_radians() / 2.0).tan();
+                let right = top * aspect_ratio;
+                // Shift the frustum towards the other eye so the views meet at
+                // the convergence distance
+                let shift = self.offset(eye) * near / self.convergence;
+
+                Projection::Frustum {
+                    left: -right - shift,
+                    right: right - shift,
+                    bottom: -top,
+                    top,
+                    near,
+                    far,
+                }
+            }
+            projection => projection,
+        };
+
+        camera.finish_projection(&projection, inverse_view)
+    }
+
+    /// The combined view and projection for `eye`
+    pub fn view_proj(&self, eye: Eye) -> Matrix4<f32> {
+        self.projection_matrix(eye) * self.view_matrix(eye)
+    }
+
+    pub fn left_view_proj(&self) -> Matrix4<f32> {
+        self.view_proj(Eye::Left)
+    }
+
+    pub fn right_view_proj(&self) -> Matrix4<f32> {
+        self.view_proj(Eye::Right)
+    }
+}
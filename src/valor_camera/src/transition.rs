@@ -0,0 +1,143 @@
+use crate::math::slerp;
+use crate::Camera;
+use cgmath::{Quaternion, Vector3};
+
+/// Curves mapping linear progress onto eased progress, both from `0.0` to
+/// `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    /// Constant speed throughout
+    Linear,
+    /// Start slowly and accelerate
+    EaseIn,
+    /// Start quickly and decelerate
+    EaseOut,
+    /// Accelerate then decelerate
+    EaseInOut,
+}
+
+impl Easing {
+    /// Ease a linear progress value `t`
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// A snapshot of the parts of a `Camera` which a transition animates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    pub position: Vector3<f32>,
+    pub orientation: Quaternion<f32>,
+    /// Field of view in degrees, for perspective cameras
+    pub fov: Option<f32>,
+}
+
+impl CameraPose {
+    /// Capture the current pose of `camera`
+    pub fn from_camera(camera: &Camera) -> Self {
+        CameraPose {
+            position: camera.position(),
+            orientation: camera.orientation(),
+            fov: camera.fov(),
+        }
+    }
+
+    /// Move `camera` into this pose
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.set_position(self.position);
+        camera.set_orientation(self.orientation);
+        if let Some(fov) = self.fov {
+            camera.set_fov(fov);
+        }
+    }
+
+    /// Blend towards `other` by `amount`
+    pub fn interpolate(&self, other: &CameraPose, amount: f32) -> CameraPose {
+        let fov = match (self.fov, other.fov) {
+            (Some(from), Some(to)) => Some(from + (to - from) * amount),
+            (from, to) => to.or(from),
+        };
+
+        CameraPose {
+            position: self.position + (other.position - self.position) * amount,
+            orientation: slerp(self.orientation, other.orientation, amount),
+            fov,
+        }
+    }
+}
+
+/// Animates a camera from one pose to another over a fixed duration.
+///
+/// # Example
+///
+/// ```
+/// # use valor_camera::{CameraBuilder, CameraPose, CameraTransition, Easing};
+/// # use cgmath::Vector3;
+/// let mut camera = CameraBuilder::new().finish();
+///
+/// let from = CameraPose::from_camera(&camera);
+/// let to = CameraPose {
+///     position: Vector3::new(0.0, 5.0, 10.0),
+///     fov: Some(60.0),
+///     ..from
+/// };
+///
+/// let mut transition = CameraTransition::new(from, to, 2.0).with_easing(Easing::EaseInOut);
+/// while transition.update(1.0 / 60.0, &mut camera) {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct CameraTransition {
+    from: CameraPose,
+    to: CameraPose,
+    duration: f32,
+    elapsed: f32,
+    easing: Easing,
+}
+
+impl CameraTransition {
+    /// Create a linear transition lasting `duration` seconds
+    pub fn new(from: CameraPose, to: CameraPose, duration: f32) -> Self {
+        CameraTransition {
+            from,
+            to,
+            duration,
+            elapsed: 0.0,
+            easing: Easing::Linear,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Linear progress through the transition, from `0.0` to `1.0`
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).min(1.0)
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// Advance the transition by `dt` seconds and move `camera` to the
+    /// blended pose. Returns `true` while the transition is still running.
+    pub fn update(&mut self, dt: f32, camera: &mut Camera) -> bool {
+        self.elapsed += dt;
+
+        let amount = self.easing.apply(self.progress());
+        self.from.interpolate(&self.to, amount).apply(camera);
+
+        !self.is_finished()
+    }
+}
@@ -0,0 +1,107 @@
+use crate::Camera;
+
+/// A rectangle of the window, in pixels, that a camera renders into.
+///
+/// The origin is the top-left corner of the window, matching the cursor
+/// positions reported by window events. valor draws into viewports for
+/// split-screen, rounding them to whole pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Viewport {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// A viewport covering a whole window of the given size
+    pub fn from_size(width: f32, height: f32) -> Self {
+        Viewport::new(0.0, 0.0, width, height)
+    }
+
+    /// The width divided by the height, for use as a camera's aspect ratio.
+    /// A viewport with no height is treated as one pixel tall.
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width / self.height.max(1.0)
+    }
+
+    /// Match `camera`'s aspect ratio to this viewport's
+    pub fn fit_camera(&self, camera: &mut Camera) {
+        camera.set_aspect_ratio(self.aspect_ratio());
+    }
+
+    /// Lay out `count` viewports for split-screen play on a window of the
+    /// given size.
+    ///
+    /// Viewports are arranged in a grid as close to square as possible, in
+    /// reading order. When the last row is not full its viewports are
+    /// stretched to fill the width, so three players get two viewports on
+    /// top and one wide viewport below. Edges are rounded to whole pixels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use valor_camera::Viewport;
+    /// let viewports = Viewport::split(800.0, 600.0, 2);
+    ///
+    /// assert_eq!(viewports[0], Viewport::new(0.0, 0.0, 400.0, 600.0));
+    /// assert_eq!(viewports[1], Viewport::new(400.0, 0.0, 400.0, 600.0));
+    /// ```
+    pub fn split(width: f32, height: f32, count: usize) -> Vec<Viewport> {
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let columns = (count as f32).sqrt().ceil() as usize;
+        let rows = count.div_ceil(columns);
+        let edge =
+            |size: f32, index: usize, parts: usize| (size * index as f32 / parts as f32).round();
+
+        (0..rows)
+            .flat_map(|row| {
+                let in_row = (count - row * columns).min(columns);
+                let top = edge(height, row, rows);
+                let bottom = edge(height, row + 1, rows);
+
+                (0..in_row).map(move |column| {
+                    let left = edge(width, column, in_row);
+                    let right = edge(width, column + 1, in_row);
+                    Viewport::new(left, top, right - left, bottom - top)
+                })
+            })
+            .collect()
+    }
+
+    /// Lay out a viewport for each of `cameras` as `split` does, and match
+    /// each camera's aspect ratio to its viewport.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use valor_camera::{CameraBuilder, Projection, Viewport};
+    /// let mut players = vec![CameraBuilder::new().finish(), CameraBuilder::new().finish()];
+    /// let viewports = Viewport::split_cameras(800.0, 600.0, &mut players);
+    ///
+    /// assert_eq!(viewports.len(), 2);
+    /// match *players[0].projection() {
+    ///     Projection::Perspective { aspect_ratio, .. } => assert_eq!(aspect_ratio, 400.0 / 600.0),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn split_cameras(width: f32, height: f32, cameras: &mut [Camera]) -> Vec<Viewport> {
+        let viewports = Viewport::split(width, height, cameras.len());
+        for (viewport, camera) in viewports.iter().zip(cameras.iter_mut()) {
+            viewport.fit_camera(camera);
+        }
+        viewports
+    }
+}
@@ -0,0 +1,120 @@
+//! Tests for cascaded shadow map splits and light projection fitting.
+
+use cgmath::{Matrix4, Vector3};
+use valor_camera::{cascade_splits, CameraBuilder, ShadowCascades};
+
+const RESOLUTION: u32 = 1024;
+
+#[test]
+fn splits_increase_from_near_to_far() {
+    for &lambda in [0.0, 0.5, 0.75, 1.0].iter() {
+        for count in 1..6 {
+            let splits = cascade_splits(0.1, 100.0, count, lambda).unwrap();
+
+            assert_eq!(splits.len(), count + 1);
+            assert!((splits[0] - 0.1).abs() < 1e-6);
+            assert!((splits[count] - 100.0).abs() < 1e-3);
+            assert!(
+                splits.windows(2).all(|pair| pair[0] < pair[1]),
+                "splits {:?} for lambda {} are not increasing",
+                splits,
+                lambda
+            );
+        }
+    }
+}
+
+#[test]
+fn splits_reject_degenerate_ranges() {
+    assert_eq!(cascade_splits(0.1, 100.0, 0, 0.5), None);
+    assert_eq!(cascade_splits(0.0, 100.0, 4, 0.5), None);
+    assert_eq!(cascade_splits(10.0, 1.0, 4, 0.5), None);
+    assert_eq!(cascade_splits(0.1, f32::INFINITY, 4, 0.5), None);
+}
+
+#[test]
+fn infinite_camera_needs_max_distance() {
+    let camera = CameraBuilder::new().with_infinite_far_plane().finish();
+
+    assert_eq!(ShadowCascades::new().splits(&camera), None);
+
+    let splits = ShadowCascades::new()
+        .with_max_distance(40.0)
+        .splits(&camera)
+        .unwrap();
+    assert!((splits.last().unwrap() - 40.0).abs() < 1e-4);
+}
+
+/// The centre of an orthographic projection's bounds in light view space,
+/// measured in texels of a shadow map `RESOLUTION` texels wide
+fn center_in_texels(projection: &Matrix4<f32>) -> (f32, f32) {
+    // `ortho` puts -(right + left) / (right - left) in the last column, and
+    // a texel is (right - left) / RESOLUTION wide
+    let half = RESOLUTION as f32 / 2.0;
+    (-projection.w.x * half, -projection.w.y * half)
+}
+
+fn assert_whole(texels: f32) {
+    assert!(
+        (texels - texels.round()).abs() < 0.01,
+        "projection is {} texels off the grid",
+        texels - texels.round()
+    );
+}
+
+#[test]
+fn projections_move_in_whole_texels() {
+    let shadows = ShadowCascades::new()
+        .with_count(3)
+        .with_resolution(RESOLUTION);
+    let light = Vector3::new(-1.0, -2.0, -0.5);
+    let mut camera = CameraBuilder::new().finish();
+
+    let mut previous: Option<Vec<Matrix4<f32>>> = None;
+    for step in 0..20 {
+        let offset = step as f32 * 0.037;
+        camera.set_position(Vector3::new(offset, 1.0, -offset * 0.5));
+
+        let projections: Vec<_> = shadows
+            .cascades(&camera, light)
+            .unwrap()
+            .iter()
+            .map(|cascade| cascade.projection)
+            .collect();
+
+        for projection in projections.iter() {
+            let (x, y) = center_in_texels(projection);
+            assert_whole(x);
+            assert_whole(y);
+        }
+
+        // Moving the camera must not change the size of the texels
+        if let Some(previous) = previous {
+            for (before, after) in previous.iter().zip(projections.iter()) {
+                assert_eq!(before.x.x, after.x.x);
+                assert_eq!(before.y.y, after.y.y);
+            }
+        }
+        previous = Some(projections);
+    }
+}
+
+#[test]
+fn projections_keep_their_size_as_the_camera_turns() {
+    let shadows = ShadowCascades::new().with_resolution(RESOLUTION);
+    let light = Vector3::new(0.3, -1.0, 0.2);
+    let mut camera = CameraBuilder::new().finish();
+
+    let sizes = |camera: &valor_camera::Camera| -> Vec<f32> {
+        shadows
+            .cascades(camera, light)
+            .unwrap()
+            .iter()
+            .map(|cascade| cascade.projection.x.x)
+            .collect()
+    };
+
+    let facing_forward = sizes(&camera);
+    camera.look_at(Vector3::new(5.0, -2.0, 3.0), Vector3::unit_y());
+    assert_eq!(sizes(&camera), facing_forward);
+}
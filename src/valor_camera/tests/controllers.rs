@@ -0,0 +1,200 @@
+//! Tests for how the orbit, fly and follow controllers move a camera.
+
+use cgmath::prelude::*;
+use cgmath::{Deg, Quaternion, Vector3};
+use valor_camera::{
+    Action, Camera, CameraBuilder, CameraController, CameraInput, FlyController, FollowController,
+    OrbitController,
+};
+
+const DT: f32 = 1.0 / 60.0;
+
+fn close(a: Vector3<f32>, b: Vector3<f32>) -> bool {
+    (a - b).magnitude() < 1e-4
+}
+
+/// Hold or release `action`
+fn action(action: Action, active: bool) -> CameraInput {
+    CameraInput::Action { action, active }
+}
+
+#[test]
+fn orbit_faces_the_target_from_its_distance() {
+    let target = Vector3::new(1.0, 2.0, 3.0);
+    let mut camera = CameraBuilder::new().finish();
+    let mut orbit = OrbitController::new(target);
+
+    orbit.set_angles(30.0, 45.0);
+    orbit.apply(&mut camera);
+    assert!(
+        ((camera.position() - target).magnitude() - orbit.distance()).abs() < 1e-4,
+        "camera should sit on the orbit"
+    );
+    assert!(
+        close(camera.forward(), (target - camera.position()).normalize()),
+        "camera should face the target"
+    );
+
+    // Zooming moves along the same line of sight
+    let forward = camera.forward();
+    orbit.handle_input(&CameraInput::Zoom(2.0));
+    orbit.update(DT, &mut camera);
+    assert!((orbit.distance() - 5.0 * 0.9 * 0.9).abs() < 1e-4);
+    assert!(close(camera.forward(), forward));
+}
+
+#[test]
+fn orbit_clamps_distance_and_elevation() {
+    let mut orbit = OrbitController::new(Vector3::zero()).with_distance_limits(2.0, 10.0);
+
+    orbit.zoom(100.0);
+    assert_eq!(orbit.distance(), 2.0);
+    orbit.zoom(-100.0);
+    assert_eq!(orbit.distance(), 10.0);
+    orbit.set_distance(0.0);
+    assert_eq!(orbit.distance(), 2.0);
+
+    // Tightening the limits pulls the current distance inside them
+    let orbit = orbit.with_distance_limits(3.0, 4.0);
+    assert_eq!(orbit.distance(), 3.0);
+
+    // Elevation stops short of the poles, where the view would flip
+    let mut orbit = OrbitController::new(Vector3::zero());
+    orbit.set_angles(0.0, 120.0);
+    assert_eq!(orbit.elevation(), 89.0);
+    orbit.set_angles(0.0, -120.0);
+    assert_eq!(orbit.elevation(), -89.0);
+}
+
+#[test]
+fn orbit_only_rotates_while_dragging() {
+    let mut orbit = OrbitController::new(Vector3::zero());
+    let look = CameraInput::Look { dx: 40.0, dy: 0.0 };
+
+    orbit.handle_input(&look);
+    assert_eq!(orbit.azimuth(), 0.0);
+
+    orbit.handle_input(&action(Action::Rotate, true));
+    orbit.handle_input(&look);
+    assert_eq!(orbit.azimuth(), -40.0 * orbit.rotate_speed);
+
+    orbit.handle_input(&action(Action::Rotate, false));
+    orbit.handle_input(&look);
+    assert_eq!(orbit.azimuth(), -40.0 * orbit.rotate_speed);
+}
+
+#[test]
+fn fly_moves_at_its_speed_in_the_facing_direction() {
+    let mut camera = CameraBuilder::new().finish();
+    let mut fly = FlyController::new().with_speed(2.0);
+
+    fly.handle_input(&action(Action::Forward, true));
+    fly.update(1.0, &mut camera);
+    assert!(close(camera.position(), Vector3::new(0.0, 0.0, -2.0)));
+
+    // Moving diagonally covers the same distance as along one axis
+    fly.handle_input(&action(Action::Right, true));
+    fly.update(1.0, &mut camera);
+    let moved = camera.position() - Vector3::new(0.0, 0.0, -2.0);
+    assert!((moved.magnitude() - 2.0).abs() < 1e-4);
+    assert!(moved.x > 0.0 && moved.z < 0.0);
+
+    // Opposite keys cancel out
+    fly.handle_input(&action(Action::Right, false));
+    fly.handle_input(&action(Action::Backward, true));
+    let position = camera.position();
+    fly.update(1.0, &mut camera);
+    assert!(close(camera.position(), position));
+}
+
+#[test]
+fn fly_pitch_stops_at_straight_up_and_down() {
+    let mut camera = CameraBuilder::new().finish();
+    let mut fly = FlyController::new();
+
+    // Far more pointer movement than a quarter turn, in several updates
+    for _ in 0..10 {
+        fly.handle_input(&CameraInput::Look {
+            dx: 0.0,
+            dy: 1000.0,
+        });
+        fly.update(DT, &mut camera);
+        assert!(camera.up().y >= -1e-4, "camera should not flip over");
+    }
+    assert!(close(camera.forward(), -Vector3::unit_y()));
+
+    for _ in 0..10 {
+        fly.handle_input(&CameraInput::Look {
+            dx: 0.0,
+            dy: -1000.0,
+        });
+        fly.update(DT, &mut camera);
+        assert!(camera.up().y >= -1e-4, "camera should not flip over");
+    }
+    assert!(close(camera.forward(), Vector3::unit_y()));
+}
+
+/// A follow controller which has snapped behind a target at the origin
+fn following(camera: &mut Camera) -> FollowController {
+    let mut follow = FollowController::new()
+        .with_offset(Vector3::new(0.0, 0.0, 4.0))
+        .with_look_offset(Vector3::zero());
+    follow.set_target(Vector3::zero(), Quaternion::one());
+    follow.update(DT, camera);
+    follow
+}
+
+#[test]
+fn follow_snaps_to_its_first_target() {
+    let mut camera = CameraBuilder::new().finish();
+    following(&mut camera);
+
+    assert!(close(camera.position(), Vector3::new(0.0, 0.0, 4.0)));
+    assert!(close(camera.forward(), -Vector3::unit_z()));
+}
+
+#[test]
+fn follow_spring_converges_without_overshooting() {
+    let mut camera = CameraBuilder::new().finish();
+    let mut follow = following(&mut camera);
+
+    follow.set_target(Vector3::new(10.0, 0.0, 0.0), Quaternion::one());
+    let desired = Vector3::new(10.0, 0.0, 4.0);
+
+    follow.update(DT, &mut camera);
+    let first = camera.position().x;
+    assert!(
+        first > 0.0 && first < 1.0,
+        "camera should ease after the target"
+    );
+
+    // A critically damped spring closes in steadily and never passes the goal
+    let mut previous = first;
+    for _ in 0..300 {
+        follow.update(DT, &mut camera);
+        let x = camera.position().x;
+        assert!(x >= previous && x <= desired.x);
+        previous = x;
+    }
+    assert!((camera.position() - desired).magnitude() < 1e-2);
+    assert!(close(
+        camera.forward(),
+        (Vector3::new(10.0, 0.0, 0.0) - camera.position()).normalize()
+    ));
+}
+
+#[test]
+fn follow_swings_behind_a_turning_target() {
+    let mut camera = CameraBuilder::new().finish();
+    let mut follow = following(&mut camera);
+
+    // Turning the target half way round eventually puts the camera in front
+    follow.set_target(Vector3::zero(), Quaternion::from_angle_y(Deg(180.0)));
+    follow.update(DT, &mut camera);
+    assert!(camera.position().z > 3.0, "rotation should lag behind");
+
+    for _ in 0..600 {
+        follow.update(DT, &mut camera);
+    }
+    assert!((camera.position() - Vector3::new(0.0, 0.0, -4.0)).magnitude() < 1e-2);
+}
@@ -0,0 +1,108 @@
+//! Tests for `CameraPath`'s arc-length parametrisation and input checking.
+
+use cgmath::prelude::*;
+use cgmath::Vector3;
+use valor_camera::{CameraPath, PathCurve};
+
+/// Distances travelled along `path` between `steps + 1` evenly spaced
+/// fractions of it, measured by walking each step in smaller pieces so
+/// bends aren't cut short
+fn step_lengths(path: &CameraPath, steps: usize) -> Vec<f32> {
+    let pieces = 16;
+    let at = |piece: usize| path.position_at(piece as f32 / (steps * pieces) as f32);
+
+    (0..steps)
+        .map(|step| {
+            (step * pieces..(step + 1) * pieces)
+                .map(|piece| (at(piece + 1) - at(piece)).magnitude())
+                .sum()
+        })
+        .collect()
+}
+
+#[test]
+fn catmull_rom_moves_at_constant_speed() {
+    // Control points far apart next to ones close together, so the curve
+    // parameter runs at very different speeds along the path
+    let path = CameraPath::catmull_rom(vec![
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.5, 0.2, 0.0),
+        Vector3::new(8.0, 3.0, -2.0),
+        Vector3::new(8.5, 3.0, -2.5),
+        Vector3::new(20.0, 0.0, 0.0),
+    ])
+    .unwrap();
+
+    let steps = 50;
+    let lengths = step_lengths(&path, steps);
+    let expected = path.length() / steps as f32;
+
+    for (step, &length) in lengths.iter().enumerate() {
+        assert!(
+            (length - expected).abs() < expected * 0.05,
+            "step {} covered {}, expected about {}",
+            step,
+            length,
+            expected
+        );
+    }
+
+    let travelled: f32 = lengths.iter().sum();
+    assert!((travelled - path.length()).abs() < path.length() * 0.01);
+}
+
+#[test]
+fn catmull_rom_passes_through_its_ends() {
+    let start = Vector3::new(1.0, 2.0, 3.0);
+    let end = Vector3::new(-4.0, 0.0, 6.0);
+    let path = CameraPath::catmull_rom(vec![start, Vector3::new(0.0, 5.0, 0.0), end]).unwrap();
+
+    assert!((path.position_at(0.0) - start).magnitude() < 1e-4);
+    assert!((path.position_at(1.0) - end).magnitude() < 1e-4);
+}
+
+#[test]
+fn bezier_moves_at_constant_speed() {
+    // Control points bunched towards the start, so the curve parameter moves
+    // slowly there and quickly later
+    let path = CameraPath::bezier(vec![
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.1, 0.0, 0.0),
+        Vector3::new(0.2, 0.0, 0.0),
+        Vector3::new(10.0, 0.0, 0.0),
+    ])
+    .unwrap();
+
+    let steps = 20;
+    let expected = path.length() / steps as f32;
+    for length in step_lengths(&path, steps) {
+        assert!((length - expected).abs() < expected * 0.05);
+    }
+}
+
+#[test]
+fn bezier_rejects_leftover_points() {
+    let point = Vector3::zero();
+
+    assert!(CameraPath::bezier(vec![point; 4]).is_some());
+    assert!(CameraPath::bezier(vec![point; 7]).is_some());
+
+    for &count in [0, 1, 2, 3, 5, 6, 8].iter() {
+        assert!(
+            CameraPath::bezier(vec![point; count]).is_none(),
+            "{} points should not form whole segments",
+            count
+        );
+    }
+}
+
+#[test]
+fn paths_need_a_whole_segment() {
+    let point = Vector3::zero();
+
+    assert!(CameraPath::catmull_rom(vec![]).is_none());
+    assert!(CameraPath::catmull_rom(vec![point]).is_none());
+    assert!(CameraPath::catmull_rom(vec![point; 2]).is_some());
+    assert!(CameraPath::new(PathCurve::CatmullRom, vec![point]).is_none());
+    assert!(CameraPath::new(PathCurve::Bezier, vec![point]).is_none());
+}
@@ -0,0 +1,94 @@
+//! Tests for reverse-Z projections and their `[0, 1]` clip-space depth.
+
+use cgmath::prelude::*;
+use cgmath::{Vector2, Vector3, Vector4};
+use valor_camera::{Camera, CameraBuilder, Projection, Viewport};
+
+/// Clip-space depth, after the perspective divide, of a point `distance`
+/// units in front of a camera looking down -Z from the origin
+fn clip_depth(camera: &Camera, distance: f32) -> f32 {
+    let clip = camera.projection_matrix() * Vector4::new(0.0, 0.0, -distance, 1.0);
+    clip.z / clip.w
+}
+
+fn reversed(far: Option<f32>) -> Camera {
+    let builder = CameraBuilder::new()
+        .with_near_plane(0.1)
+        .with_reverse_z(true);
+    match far {
+        Some(far) => builder.with_far_plane(far).finish(),
+        None => builder.with_infinite_far_plane().finish(),
+    }
+}
+
+#[test]
+fn near_plane_maps_to_one_and_far_plane_to_zero() {
+    let camera = reversed(Some(100.0));
+    assert!((clip_depth(&camera, 0.1) - 1.0).abs() < 1e-6);
+    assert!(clip_depth(&camera, 100.0).abs() < 1e-6);
+    assert!(clip_depth(&camera, 1.0) > clip_depth(&camera, 2.0));
+
+    let camera = reversed(None);
+    assert!((clip_depth(&camera, 0.1) - 1.0).abs() < 1e-6);
+    assert!(clip_depth(&camera, 1e30) >= 0.0);
+    assert!(clip_depth(&camera, 1e30) < 1e-6);
+}
+
+#[test]
+fn orthographic_maps_into_zero_to_one() {
+    let projection = Projection::Orthographic {
+        left: -1.0,
+        right: 1.0,
+        bottom: -1.0,
+        top: 1.0,
+        near: 1.0,
+        far: 11.0,
+    };
+    let matrix = projection.reversed_matrix(1.0);
+    let depth = |distance: f32| (matrix * Vector4::new(0.0, 0.0, -distance, 1.0)).z;
+
+    assert!((depth(1.0) - 1.0).abs() < 1e-6);
+    assert!((depth(6.0) - 0.5).abs() < 1e-6);
+    assert!(depth(11.0).abs() < 1e-6);
+}
+
+#[test]
+fn distant_points_keep_distinct_depths() {
+    // Float depth near zero has far more precision than near one, which is
+    // where a standard projection puts distant geometry
+    let camera = reversed(None);
+    let depths: Vec<f32> = [10_000.0, 10_010.0, 10_020.0]
+        .iter()
+        .map(|&distance| clip_depth(&camera, distance))
+        .collect();
+
+    assert!(depths[0] > depths[1]);
+    assert!(depths[1] > depths[2]);
+}
+
+#[test]
+fn project_and_unproject_round_trip() {
+    let viewport = Viewport::from_size(800.0, 600.0);
+    let point = Vector3::new(1.0, -0.5, -40.0);
+
+    for camera in [reversed(Some(100.0)), reversed(None)].iter() {
+        let screen = camera.project(point, &viewport).unwrap();
+        assert!(screen.z > 0.0 && screen.z < 1.0);
+
+        let world = camera
+            .unproject(Vector2::new(screen.x, screen.y), screen.z, &viewport)
+            .unwrap();
+        assert!((world - point).magnitude() < 1e-2);
+    }
+
+    // Depth from `project` runs from near to far whichever way the depth
+    // buffer is laid out
+    let camera = reversed(Some(100.0));
+    let near = camera
+        .project(Vector3::new(0.0, 0.0, -1.0), &viewport)
+        .unwrap();
+    let far = camera
+        .project(Vector3::new(0.0, 0.0, -50.0), &viewport)
+        .unwrap();
+    assert!(near.z < far.z);
+}
@@ -0,0 +1,133 @@
+use generational_arena::Index;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// Type-erased storage for a single kind of component.
+trait Storage {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// An empty store for the same kind of component
+    fn empty(&self) -> Box<dyn Storage>;
+    /// Move the component attached to `from`, if any, into `dest` as `to`.
+    /// `dest` must store the same kind of component.
+    fn transfer(&mut self, from: Index, dest: &mut dyn Storage, to: Index);
+}
+
+impl<C: 'static> Storage for HashMap<Index, C> {
+    fn empty(&self) -> Box<dyn Storage> {
+        Box::new(HashMap::<Index, C>::new())
+    }
+
+    fn transfer(&mut self, from: Index, dest: &mut dyn Storage, to: Index) {
+        if let Some(component) = self.remove(&from) {
+            dest.as_any_mut()
+                .downcast_mut::<Self>()
+                .expect("components transferred between stores of different types")
+                .insert(to, component);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Typed data attached to nodes, keyed first by component type and then by
+/// node.
+#[derive(Default)]
+pub struct Components {
+    stores: HashMap<TypeId, Box<dyn Storage>>,
+}
+
+impl Components {
+    /// Create an empty component map
+    pub fn new() -> Self {
+        Components {
+            stores: HashMap::new(),
+        }
+    }
+
+    fn store<C: 'static>(&self) -> Option<&HashMap<Index, C>> {
+        self.stores
+            .get(&TypeId::of::<C>())
+            .and_then(|store| store.as_any().downcast_ref())
+    }
+
+    fn store_mut<C: 'static>(&mut self) -> &mut HashMap<Index, C> {
+        self.stores
+            .entry(TypeId::of::<C>())
+            .or_insert_with(|| Box::new(HashMap::<Index, C>::new()))
+            .as_any_mut()
+            .downcast_mut()
+            .expect("component store registered under the wrong type")
+    }
+
+    /// Attach `component` to `id`, returning the previous component of the
+    /// same type if there was one
+    pub fn insert<C: 'static>(&mut self, id: Index, component: C) -> Option<C> {
+        self.store_mut().insert(id, component)
+    }
+
+    /// Borrow the component of type `C` attached to `id`
+    pub fn get<C: 'static>(&self, id: Index) -> Option<&C> {
+        self.store().and_then(|store| store.get(&id))
+    }
+
+    /// Mutably borrow the component of type `C` attached to `id`
+    pub fn get_mut<C: 'static>(&mut self, id: Index) -> Option<&mut C> {
+        self.stores
+            .get_mut(&TypeId::of::<C>())
+            .and_then(|store| store.as_any_mut().downcast_mut::<HashMap<Index, C>>())
+            .and_then(|store| store.get_mut(&id))
+    }
+
+    /// Detach the component of type `C` from `id`, returning it
+    pub fn remove<C: 'static>(&mut self, id: Index) -> Option<C> {
+        self.stores
+            .get_mut(&TypeId::of::<C>())
+            .and_then(|store| store.as_any_mut().downcast_mut::<HashMap<Index, C>>())
+            .and_then(|store| store.remove(&id))
+    }
+
+    /// Move every component attached to `from` into `dest`, attaching them to
+    /// `to` there
+    pub(crate) fn transfer(&mut self, from: Index, dest: &mut Components, to: Index) {
+        for (&type_id, store) in &mut self.stores {
+            let target = dest.stores.entry(type_id).or_insert_with(|| store.empty());
+            store.transfer(from, target.as_mut(), to);
+        }
+    }
+}
+
+/// A set of component types which can be fetched together for a node.
+///
+/// Implemented for tuples of up to four component types, e.g. `(A,)` or
+/// `(A, B)`.
+pub trait ComponentQuery<'a> {
+    /// References to each of the components in the set
+    type Item;
+
+    /// Fetch every component in the set for `id`, or `None` if any is missing
+    fn fetch(components: &'a Components, id: Index) -> Option<Self::Item>;
+}
+
+macro_rules! impl_component_query {
+    ($($component:ident),+) => {
+        impl<'a, $($component: 'static),+> ComponentQuery<'a> for ($($component,)+) {
+            type Item = ($(&'a $component,)+);
+
+            fn fetch(components: &'a Components, id: Index) -> Option<Self::Item> {
+                Some(($(components.get::<$component>(id)?,)+))
+            }
+        }
+    };
+}
+
+impl_component_query!(A);
+impl_component_query!(A, B);
+impl_component_query!(A, B, C);
+impl_component_query!(A, B, C, D);
@@ -1,35 +1,300 @@
 //! An easy way to create, manipulate, and traverse component trees.
+mod component;
 mod node;
+mod node_id;
 mod traversal;
 
+pub use crate::component::{ComponentQuery, Components};
 pub use crate::node::Node;
+pub use crate::node_id::NodeId;
 pub use crate::traversal::Traversal;
-use cgmath::Vector3;
+use cgmath::{Matrix4, One, Vector3};
 use generational_arena::Arena;
 pub use generational_arena::Index;
+use std::collections::{HashMap, HashSet};
 
 /// A directed acyclic graph for models with transformations at each node.
+///
+/// Other scenes may be mounted beneath any node, which lets separately built
+/// scenes be attached and detached at runtime without disturbing the indices
+/// of their nodes. `traverse` visits mounted scenes and identifies their nodes
+/// by `NodeId`; every other method only sees the scene's own nodes. A mounted
+/// scene can instead be merged into the scene's own nodes, and split back out
+/// again later.
 pub struct Scene<T> {
     arena: Arena<Node<T>>,
-    root: Index,
+    roots: Vec<Index>,
+    components: Components,
+    mounts: HashMap<Index, Scene<T>>,
+    merged: HashMap<Index, Merged>,
+}
+
+/// The nodes of a scene merged beneath a mount point
+struct Merged {
+    /// The merged scene's roots, which became children of the mount point
+    roots: Vec<Index>,
+    /// Every node moved in by the merge
+    nodes: Vec<Index>,
+}
+
+impl Merged {
+    fn remap(&self, remap: &HashMap<Index, Index>) -> Self {
+        let lookup = |ids: &[Index]| ids.iter().filter_map(|id| remap.get(id).cloned()).collect();
+        Merged {
+            roots: lookup(&self.roots),
+            nodes: lookup(&self.nodes),
+        }
+    }
 }
 
 impl<T> Scene<T> {
     /// Creates a new scene and inserts an empty root node
     pub fn new(root: T) -> Self {
         let mut arena = Arena::new();
-        let root = arena.insert(Node::new(root));
-        Scene { arena, root }
+        let roots = vec![arena.insert(Node::new(root))];
+        let components = Components::new();
+        let mounts = HashMap::new();
+        let merged = HashMap::new();
+        Scene {
+            arena,
+            roots,
+            components,
+            mounts,
+            merged,
+        }
     }
 
     pub fn create_node(&mut self, data: T) -> Index {
         let node = Node::new(data);
-        let index = self.arena.insert(node);
-        index
+        self.arena.insert(node)
     }
 
+    /// The root node created along with the scene
     pub fn get_root(&self) -> Index {
-        self.root
+        self.roots[0]
+    }
+
+    /// Insert an additional root node, which is traversed after the existing
+    /// roots
+    pub fn add_root(&mut self, data: T) -> Index {
+        let index = self.create_node(data);
+        self.roots.push(index);
+        index
+    }
+
+    /// Every root of the scene, in traversal order
+    pub fn roots(&self) -> &[Index] {
+        &self.roots
+    }
+
+    /// Attach `scene` beneath the node `at`, so that its roots are traversed
+    /// as children of `at`.
+    ///
+    /// Returns the scene previously mounted at `at`, if any. If `at` does not
+    /// exist then nothing is mounted and `scene` is handed back as the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use valor_scene::Scene;
+    /// let mut world = Scene::new("world");
+    /// let anchor = world.create_node("anchor");
+    /// world.add_child(world.get_root(), anchor);
+    ///
+    /// let mut chunk = Scene::new("chunk");
+    /// let tree = chunk.create_node("tree");
+    /// chunk.add_child(chunk.get_root(), tree);
+    ///
+    /// assert!(world.mount(anchor, chunk).is_ok());
+    /// let names: Vec<_> = world.traverse().map(|(_, name, _)| *name).collect();
+    /// assert_eq!(names, ["world", "anchor", "chunk", "tree"]);
+    ///
+    /// // Nodes of the chunk are identified through the anchor they hang from
+    /// let (id, _, _) = world.traverse().last().unwrap();
+    /// assert_eq!(id.mounts(), [anchor]);
+    /// assert_eq!(world.resolve(&id).map(|node| node.data), Some("tree"));
+    ///
+    /// let chunk = world.unmount(anchor).unwrap();
+    /// assert_eq!(chunk.get(tree).map(|node| node.data), Some("tree"));
+    /// ```
+    // The rejected scene is handed back whole rather than boxed, like the
+    // replaced one
+    #[allow(clippy::result_large_err)]
+    pub fn mount(&mut self, at: Index, scene: Scene<T>) -> Result<Option<Scene<T>>, Scene<T>> {
+        if self.arena.contains(at) {
+            Ok(self.mounts.insert(at, scene))
+        } else {
+            Err(scene)
+        }
+    }
+
+    /// Detach and return the scene mounted beneath `at`. Indices into the
+    /// returned scene remain valid.
+    pub fn unmount(&mut self, at: Index) -> Option<Scene<T>> {
+        self.mounts.remove(&at)
+    }
+
+    pub fn mounted(&self, at: Index) -> Option<&Scene<T>> {
+        self.mounts.get(&at)
+    }
+
+    pub fn mounted_mut(&mut self, at: Index) -> Option<&mut Scene<T>> {
+        self.mounts.get_mut(&at)
+    }
+
+    /// Move the scene mounted beneath `at` into this scene, so that its nodes
+    /// are seen by every method rather than only by `traverse`. Its roots
+    /// become children of `at`, traversed in the same order as when mounted,
+    /// and its components and mounted scenes move along with its nodes.
+    ///
+    /// Returns a table from each node's index in the mounted scene to its new
+    /// index in this one, or `None` if no scene is mounted beneath `at` or one
+    /// has already been merged there.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use valor_scene::Scene;
+    /// let mut world = Scene::new("world");
+    /// let anchor = world.create_node("anchor");
+    /// world.add_child(world.get_root(), anchor);
+    ///
+    /// let mut chunk = Scene::new("chunk");
+    /// let tree = chunk.create_node("tree");
+    /// chunk.add_child(chunk.get_root(), tree);
+    ///
+    /// assert!(world.mount(anchor, chunk).is_ok());
+    /// let merged_tree = world.merge(anchor).unwrap()[&tree];
+    /// assert_eq!(world.get(merged_tree).map(|node| node.data), Some("tree"));
+    /// assert_eq!(world.query(|name| *name == "tree").count(), 1);
+    ///
+    /// // Splitting the chunk back out renumbers its nodes again
+    /// let (chunk, remap) = world.unmerge(anchor).unwrap();
+    /// assert_eq!(chunk.get(remap[&merged_tree]).map(|node| node.data), Some("tree"));
+    /// assert_eq!(world.query(|name| *name == "tree").count(), 0);
+    /// ```
+    pub fn merge(&mut self, at: Index) -> Option<HashMap<Index, Index>> {
+        if self.merged.contains_key(&at) {
+            return None;
+        }
+        let mut scene = self.mounts.remove(&at)?;
+
+        let ids: Vec<Index> = scene.arena.iter().map(|(id, _)| id).collect();
+        let remap = scene.move_nodes(&ids, self);
+        let merged = Merged {
+            roots: scene.roots.clone(),
+            nodes: ids,
+        }
+        .remap(&remap);
+
+        if let Some(node) = self.arena.get(at) {
+            for &root in &merged.roots {
+                node.add_child(root);
+            }
+        }
+        self.merged.insert(at, merged);
+        Some(remap)
+    }
+
+    /// Split the scene merged beneath `at` back out of this scene, undoing
+    /// `merge`. The nodes it brought in are moved into a new scene, along with
+    /// any nodes since attached beneath them, and are no longer children of
+    /// `at`.
+    ///
+    /// Returns the scene and a table from each node's index in this scene to
+    /// its index in the returned one, or `None` if nothing is merged beneath
+    /// `at`. Indices of the rest of this scene remain valid, but children
+    /// added elsewhere in this scene which point at the moved nodes are left
+    /// dangling and skipped by traversal.
+    pub fn unmerge(&mut self, at: Index) -> Option<(Scene<T>, HashMap<Index, Index>)> {
+        let merged = self.merged.remove(&at)?;
+        if let Some(node) = self.arena.get(at) {
+            node.children
+                .borrow_mut()
+                .retain(|child| !merged.roots.contains(child));
+        }
+
+        // Take nodes attached beneath the merged ones since, too, so none of
+        // the returned scene's children dangle
+        let mut ids = merged.nodes.clone();
+        let mut seen: HashSet<Index> = ids.iter().cloned().collect();
+        let mut stack = merged.nodes.clone();
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.arena.get(id) {
+                for &child in node.children.borrow().iter() {
+                    if seen.insert(child) {
+                        ids.push(child);
+                        stack.push(child);
+                    }
+                }
+            }
+        }
+
+        let mut scene = Scene {
+            arena: Arena::new(),
+            roots: Vec::new(),
+            components: Components::new(),
+            mounts: HashMap::new(),
+            merged: HashMap::new(),
+        };
+        let remap = self.move_nodes(&ids, &mut scene);
+        scene.roots = merged.remap(&remap).roots;
+        Some((scene, remap))
+    }
+
+    /// Move the nodes `ids` into `dest`, along with their components, mounted
+    /// scenes and merges. Children among the moved nodes are renumbered.
+    fn move_nodes(&mut self, ids: &[Index], dest: &mut Scene<T>) -> HashMap<Index, Index> {
+        let mut remap = HashMap::new();
+        for &id in ids {
+            if let Some(node) = self.arena.remove(id) {
+                let to = dest.arena.insert(node);
+                self.components.transfer(id, &mut dest.components, to);
+                if let Some(scene) = self.mounts.remove(&id) {
+                    dest.mounts.insert(to, scene);
+                }
+                remap.insert(id, to);
+            }
+        }
+
+        for (&id, &to) in &remap {
+            if let Some(node) = dest.arena.get_mut(to) {
+                for child in node.children.get_mut() {
+                    if let Some(&moved) = remap.get(child) {
+                        *child = moved;
+                    }
+                }
+            }
+            if let Some(merged) = self.merged.remove(&id) {
+                dest.merged.insert(to, merged.remap(&remap));
+            }
+        }
+        remap
+    }
+
+    /// The scene owning the node `id`, found by following its mount points
+    /// from this scene. The node is `id.index()` within it.
+    pub fn owner(&self, id: &NodeId) -> Option<&Scene<T>> {
+        id.mounts()
+            .iter()
+            .try_fold(self, |scene, &at| scene.mounted(at))
+    }
+
+    pub fn owner_mut(&mut self, id: &NodeId) -> Option<&mut Scene<T>> {
+        let mut scene = self;
+        for at in id.mounts() {
+            scene = scene.mounts.get_mut(at)?;
+        }
+        Some(scene)
+    }
+
+    /// Look up a node in this scene or any scene mounted beneath it
+    pub fn resolve(&self, id: &NodeId) -> Option<&Node<T>> {
+        self.owner(id)?.get(id.index())
+    }
+
+    pub fn resolve_mut(&mut self, id: &NodeId) -> Option<&mut Node<T>> {
+        self.owner_mut(id)?.get_mut(id.index())
     }
 
     pub fn translate(&mut self, id: Index, translation: Vector3<f32>) {
@@ -52,7 +317,118 @@ impl<T> Scene<T> {
         self.arena.get_mut(id)
     }
 
-    pub fn traverse(&self) -> Traversal<T> {
-        Traversal::new(&self.arena, self.root)
+    /// Calculate the world transform of a node by combining the transforms of
+    /// its ancestors, as `traverse` would. Nodes in mounted scenes are not
+    /// searched; ask the scene returned by `owner` instead, and combine the
+    /// result with the world transform of the mount point.
+    ///
+    /// Returns `None` if the node is not reachable from any root.
+    pub fn world_transform(&self, id: Index) -> Option<Matrix4<f32>> {
+        self.roots
+            .iter()
+            .find_map(|&root| self.find_world_transform(root, id, Matrix4::one()))
+    }
+
+    fn find_world_transform(
+        &self,
+        from: Index,
+        id: Index,
+        parent: Matrix4<f32>,
+    ) -> Option<Matrix4<f32>> {
+        let node = self.arena.get(from)?;
+        let transform = parent * node.transform;
+
+        if from == id {
+            return Some(transform);
+        }
+
+        node.children
+            .borrow()
+            .iter()
+            .find_map(|&child| self.find_world_transform(child, id, transform))
+    }
+
+    pub fn traverse(&self) -> Traversal<'_, T> {
+        Traversal::new(self)
+    }
+
+    /// Iterate over every node in the scene, whether or not it is reachable
+    /// from the root. Order is unspecified.
+    ///
+    /// Nodes of mounted scenes are not included.
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> + '_ {
+        self.arena.iter().map(|(id, node)| (id, &node.data))
+    }
+
+    /// Iterate over every node in the scene whose data matches `predicate`.
+    /// Nodes of mounted scenes are not included.
+    pub fn query<'a, P>(&'a self, mut predicate: P) -> impl Iterator<Item = (Index, &'a T)> + 'a
+    where
+        P: FnMut(&T) -> bool + 'a,
+    {
+        self.iter().filter(move |(_, data)| predicate(data))
+    }
+
+    /// Attach a component to a node, replacing and returning any existing
+    /// component of the same type.
+    ///
+    /// If the node does not exist then nothing is attached and the component
+    /// is handed back as the error.
+    pub fn insert_component<C: 'static>(
+        &mut self,
+        id: Index,
+        component: C,
+    ) -> Result<Option<C>, C> {
+        if self.arena.contains(id) {
+            Ok(self.components.insert(id, component))
+        } else {
+            Err(component)
+        }
+    }
+
+    /// Borrow the component of type `C` attached to a node, if it has one
+    pub fn get_component<C: 'static>(&self, id: Index) -> Option<&C> {
+        self.components.get(id)
+    }
+
+    /// Mutably borrow the component of type `C` attached to a node, if it has
+    /// one
+    pub fn get_component_mut<C: 'static>(&mut self, id: Index) -> Option<&mut C> {
+        self.components.get_mut(id)
+    }
+
+    /// Detach the component of type `C` from a node and return it. Components
+    /// of other types stay attached.
+    pub fn remove_component<C: 'static>(&mut self, id: Index) -> Option<C> {
+        self.components.remove(id)
+    }
+
+    /// Iterate over every node which has all of the components in `Q`.
+    /// Components of mounted scenes are not included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use valor_scene::Scene;
+    /// struct Light(f32);
+    /// struct Name(&'static str);
+    ///
+    /// let mut scene = Scene::new(());
+    /// let lamp = scene.create_node(());
+    /// assert!(scene.insert_component(lamp, Light(0.8)).is_ok());
+    /// assert!(scene.insert_component(lamp, Name("lamp")).is_ok());
+    ///
+    /// for (_id, (light, name)) in scene.query_components::<(Light, Name)>() {
+    ///     println!("{} has intensity {}", name.0, light.0);
+    /// }
+    /// ```
+    pub fn query_components<'a, Q>(&'a self) -> impl Iterator<Item = (Index, Q::Item)> + 'a
+    where
+        Q: ComponentQuery<'a>,
+    {
+        let components = &self.components;
+        self.arena
+            .iter()
+            .filter_map(move |(id, _)| Q::fetch(components, id).map(|item| (id, item)))
     }
 }
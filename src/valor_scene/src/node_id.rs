@@ -0,0 +1,52 @@
+use generational_arena::Index;
+use std::rc::Rc;
+
+/// Identifies a node in a scene, or in one of the scenes mounted beneath it.
+///
+/// Every scene numbers its nodes independently, so an `Index` alone can't say
+/// which scene a node belongs to once scenes are mounted inside each other.
+/// A `NodeId` also records the mount points to follow, outermost first, to
+/// reach the scene owning the node. Use `Scene::owner` or `Scene::resolve` to
+/// look it up.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    mounts: Rc<[Index]>,
+    index: Index,
+}
+
+impl NodeId {
+    /// Identify a node belonging to the scene itself
+    pub fn new(index: Index) -> Self {
+        NodeId {
+            mounts: Rc::from(Vec::new()),
+            index,
+        }
+    }
+
+    /// Identify a node in the scene reached through `mounts`
+    pub(crate) fn mounted(mounts: Rc<[Index]>, index: Index) -> Self {
+        NodeId { mounts, index }
+    }
+
+    /// The node's index within the scene owning it
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    /// The mount points leading to the scene owning the node, outermost
+    /// first. Empty for nodes of the scene itself.
+    pub fn mounts(&self) -> &[Index] {
+        &self.mounts
+    }
+
+    /// Whether the node belongs to a mounted scene
+    pub fn is_mounted(&self) -> bool {
+        !self.mounts.is_empty()
+    }
+}
+
+impl From<Index> for NodeId {
+    fn from(index: Index) -> Self {
+        NodeId::new(index)
+    }
+}
@@ -1,66 +1,110 @@
-use crate::Node;
+use crate::{NodeId, Scene};
 use cgmath::{Matrix4, One};
-use generational_arena::{Arena, Index};
+use generational_arena::Index;
+use std::rc::Rc;
 
 type Transform = Matrix4<f32>;
 
-#[derive(Debug)]
-enum TraversalAction {
-  Entry(Index),
-  Descend(Index),
+/// The scene a node belongs to, and the mount points leading to it
+struct Owner<'a, T> {
+  scene: &'a Scene<T>,
+  mounts: Rc<[Index]>,
+}
+
+// Derived `Clone` would needlessly require `T: Clone`
+impl<'a, T> Clone for Owner<'a, T> {
+  fn clone(&self) -> Self {
+    Owner {
+      scene: self.scene,
+      mounts: self.mounts.clone(),
+    }
+  }
+}
+
+enum TraversalAction<'a, T> {
+  Entry(Owner<'a, T>, Index),
+  Descend(&'a Scene<T>, Index),
   Ascend,
 }
 
 /// `Iterator` over `Node`s in a `Scene`.
+///
+/// Scenes mounted beneath a node are visited as children of that node. Each
+/// node is yielded with a `NodeId` recording which mounted scene it belongs
+/// to, which `Scene::resolve` looks up.
 pub struct Traversal<'a, T> {
   /// Stack of transforms
   transforms: Vec<Transform>,
   /// Action
-  action_stream: Vec<TraversalAction>,
-  // Pool of nodes to retrieve from
-  arena: &'a Arena<Node<T>>,
+  action_stream: Vec<TraversalAction<'a, T>>,
 }
 
 impl<'a, T> Traversal<'a, T> {
-  /// Create a new new traversal over children of a given node
-  pub fn new(arena: &'a Arena<Node<T>>, root: Index) -> Self {
-    // Stack begins with the root element's ID and transform
+  /// Create a new traversal over every root of a scene
+  pub fn new(scene: &'a Scene<T>) -> Self {
+    // Stack begins with the root elements' IDs and an identity transform
     let transforms = vec![Matrix4::one()];
-    let action_stream = vec![TraversalAction::Entry(root)];
+    let owner = Owner {
+      scene,
+      mounts: Rc::from(Vec::new()),
+    };
+    let action_stream = Self::entries(owner);
 
     Traversal {
       transforms,
       action_stream,
-      arena,
     }
   }
 
-  pub fn add_children(&mut self, index: Index, children: &Vec<Index>) {
-    let mut actions = children
+  /// Entry actions for the roots of `scene`, in stack order
+  fn entries(owner: Owner<'a, T>) -> Vec<TraversalAction<'a, T>> {
+    owner
+      .scene
+      .roots()
       .iter()
-      .cloned()
-      .map(|index| TraversalAction::Entry(index))
-      .collect();
+      .rev()
+      .map(|&root| TraversalAction::Entry(owner.clone(), root))
+      .collect()
+  }
 
+  fn add_children(&mut self, owner: &Owner<'a, T>, index: Index, children: &[Index]) {
     self.action_stream.push(TraversalAction::Ascend);
-    self.action_stream.append(&mut actions);
-    self.action_stream.push(TraversalAction::Descend(index));
+
+    // A mounted scene is visited after the node's own children
+    if let Some(scene) = owner.scene.mounted(index) {
+      let mut mounts = owner.mounts.to_vec();
+      mounts.push(index);
+      let mounted = Owner {
+        scene,
+        mounts: Rc::from(mounts),
+      };
+      self.action_stream.append(&mut Self::entries(mounted));
+    }
+
+    self.action_stream.extend(
+      children
+        .iter()
+        .map(|&child| TraversalAction::Entry(owner.clone(), child)),
+    );
+    self
+      .action_stream
+      .push(TraversalAction::Descend(owner.scene, index));
   }
 }
 
 impl<'a, T> Iterator for Traversal<'a, T> {
-  type Item = (Index, &'a T, Transform);
+  type Item = (NodeId, &'a T, Transform);
 
   fn next(&mut self) -> Option<Self::Item> {
     match self.action_stream.pop() {
       Some(action) => match action {
-        TraversalAction::Entry(index) => {
-          if let Some(node) = self.arena.get(index) {
+        TraversalAction::Entry(owner, index) => {
+          if let Some(node) = owner.scene.get(index) {
             let children = node.children.borrow();
 
-            // If this node has children, process them next
-            if !children.is_empty() {
-              self.add_children(index, &children);
+            // If this node has children or a mounted scene, process them next
+            if !children.is_empty() || owner.scene.mounted(index).is_some() {
+              self.add_children(&owner, index, &children);
             };
 
             // Calculate ancestor transform times this node's transform
@@ -71,13 +115,13 @@ impl<'a, T> Iterator for Traversal<'a, T> {
 
             let data = &node.data;
 
-            Some((index, data, transform))
+            Some((NodeId::mounted(owner.mounts, index), data, transform))
           } else {
             self.next()
           }
         }
-        TraversalAction::Descend(index) => {
-          if let Some(node) = self.arena.get(index) {
+        TraversalAction::Descend(scene, index) => {
+          if let Some(node) = scene.get(index) {
             // Calculate cumulative transform for ancestors through the parent
             let transform = match self.transforms.last() {
               Some(t) => t * node.transform,
@@ -0,0 +1,99 @@
+//! Tests for predicate queries and typed components on a `Scene`.
+
+use std::collections::HashSet;
+use valor_scene::{Index, Scene};
+
+#[derive(Debug, PartialEq)]
+struct Light(f32);
+
+#[derive(Debug, PartialEq)]
+struct Name(&'static str);
+
+/// A scene with a root and three nodes, only two of which are attached to it
+fn scene() -> (Scene<&'static str>, [Index; 3]) {
+    let mut scene = Scene::new("root");
+    let lamp = scene.create_node("lamp");
+    let torch = scene.create_node("torch");
+    let rock = scene.create_node("rock");
+    scene.add_child(scene.get_root(), lamp);
+    scene.add_child(scene.get_root(), rock);
+    (scene, [lamp, torch, rock])
+}
+
+#[test]
+fn query_matches_unreachable_nodes() {
+    let (scene, [lamp, torch, _]) = scene();
+
+    let lit: HashSet<_> = scene
+        .query(|name| *name == "lamp" || *name == "torch")
+        .map(|(id, _)| id)
+        .collect();
+
+    // `torch` is found even though it isn't reachable from the root
+    assert_eq!(lit, [lamp, torch].iter().cloned().collect());
+    assert_eq!(scene.query(|_| false).count(), 0);
+    assert_eq!(scene.query(|_| true).count(), 4);
+}
+
+#[test]
+fn insert_component_replaces_by_type() {
+    let (mut scene, [lamp, _, _]) = scene();
+
+    assert_eq!(scene.insert_component(lamp, Light(0.5)), Ok(None));
+    assert_eq!(scene.insert_component(lamp, Name("lamp")), Ok(None));
+    assert_eq!(
+        scene.insert_component(lamp, Light(0.8)),
+        Ok(Some(Light(0.5)))
+    );
+
+    assert_eq!(scene.get_component::<Light>(lamp), Some(&Light(0.8)));
+    assert_eq!(scene.get_component::<Name>(lamp), Some(&Name("lamp")));
+
+    scene.get_component_mut::<Light>(lamp).unwrap().0 = 1.0;
+    assert_eq!(scene.get_component::<Light>(lamp), Some(&Light(1.0)));
+}
+
+#[test]
+fn insert_component_on_missing_node_hands_it_back() {
+    // The second node of another scene's arena has no counterpart in a scene
+    // holding only its root
+    let (_, [lamp, _, _]) = scene();
+    let mut scene = Scene::new("root");
+
+    assert_eq!(scene.insert_component(lamp, Light(1.0)), Err(Light(1.0)));
+    assert_eq!(scene.get_component::<Light>(lamp), None);
+    assert_eq!(scene.query_components::<(Light,)>().count(), 0);
+}
+
+#[test]
+fn query_components_requires_every_type() {
+    let (mut scene, [lamp, torch, rock]) = scene();
+    assert!(scene.insert_component(lamp, Light(0.8)).is_ok());
+    assert!(scene.insert_component(lamp, Name("lamp")).is_ok());
+    assert!(scene.insert_component(torch, Light(0.3)).is_ok());
+    assert!(scene.insert_component(torch, Name("torch")).is_ok());
+    assert!(scene.insert_component(rock, Name("rock")).is_ok());
+
+    let mut found: Vec<_> = scene
+        .query_components::<(Light, Name)>()
+        .map(|(id, (light, name))| (id, light.0, name.0))
+        .collect();
+    found.sort_by(|a, b| a.2.cmp(b.2));
+    assert_eq!(found, [(lamp, 0.8, "lamp"), (torch, 0.3, "torch")]);
+
+    let names = scene.query_components::<(Name,)>().count();
+    assert_eq!(names, 3);
+}
+
+#[test]
+fn remove_component_leaves_other_types() {
+    let (mut scene, [lamp, _, rock]) = scene();
+    assert!(scene.insert_component(lamp, Light(0.8)).is_ok());
+    assert!(scene.insert_component(lamp, Name("lamp")).is_ok());
+
+    assert_eq!(scene.remove_component::<Light>(lamp), Some(Light(0.8)));
+    assert_eq!(scene.remove_component::<Light>(lamp), None);
+    assert_eq!(scene.remove_component::<Light>(rock), None);
+    assert_eq!(scene.get_component::<Name>(lamp), Some(&Name("lamp")));
+    assert_eq!(scene.query_components::<(Light, Name)>().count(), 0);
+}
@@ -0,0 +1,205 @@
+//! Tests for mounting scenes beneath each other and merging them in.
+
+use cgmath::{Matrix4, Vector3};
+use valor_scene::{Index, NodeId, Scene};
+
+/// A world with an `anchor` node translated along x, and a `chunk` scene
+/// whose root is translated along y and has a `tree` child
+fn world_and_chunk() -> (Scene<&'static str>, Index, Scene<&'static str>, Index) {
+    let mut world = Scene::new("world");
+    let anchor = world.create_node("anchor");
+    world.add_child(world.get_root(), anchor);
+    world.translate(anchor, Vector3::new(1.0, 0.0, 0.0));
+
+    let mut chunk = Scene::new("chunk");
+    let tree = chunk.create_node("tree");
+    chunk.add_child(chunk.get_root(), tree);
+    chunk.translate(chunk.get_root(), Vector3::new(0.0, 2.0, 0.0));
+
+    (world, anchor, chunk, tree)
+}
+
+/// The id and transform of the traversed node named `name`
+fn find(scene: &Scene<&'static str>, name: &str) -> (NodeId, Matrix4<f32>) {
+    scene
+        .traverse()
+        .find(|(_, data, _)| **data == name)
+        .map(|(id, _, transform)| (id, transform))
+        .unwrap_or_else(|| panic!("{} not traversed", name))
+}
+
+#[test]
+fn traversal_crosses_mount_points() {
+    let (mut world, anchor, chunk, tree) = world_and_chunk();
+    let sibling = world.create_node("sibling");
+    world.add_child(anchor, sibling);
+    assert!(world.mount(anchor, chunk).is_ok());
+
+    let names: Vec<_> = world.traverse().map(|(_, name, _)| *name).collect();
+    assert_eq!(names, ["world", "anchor", "sibling", "chunk", "tree"]);
+
+    // The mounted nodes inherit the mount point's transform
+    let (id, transform) = find(&world, "tree");
+    assert_eq!(id.mounts(), [anchor]);
+    assert_eq!(id.index(), tree);
+    assert_eq!(
+        transform,
+        Matrix4::from_translation(Vector3::new(1.0, 2.0, 0.0))
+    );
+
+    // Nodes of the world itself are not identified through a mount
+    let (id, _) = find(&world, "sibling");
+    assert!(!id.is_mounted());
+}
+
+#[test]
+fn nested_mounts_resolve_through_each_mount_point() {
+    let (mut world, anchor, mut chunk, tree) = world_and_chunk();
+    let prop = Scene::new("prop");
+    assert!(chunk.mount(tree, prop).is_ok());
+    assert!(world.mount(anchor, chunk).is_ok());
+
+    let (id, _) = find(&world, "prop");
+    assert_eq!(id.mounts(), [anchor, tree]);
+    assert_eq!(world.resolve(&id).map(|node| node.data), Some("prop"));
+
+    let owner = world.owner(&id).unwrap();
+    assert_eq!(
+        owner.get(owner.get_root()).map(|node| node.data),
+        Some("prop")
+    );
+
+    // The chunk owns the tree, and the world owns the anchor
+    let (id, _) = find(&world, "tree");
+    let owner = world.owner(&id).unwrap();
+    assert_eq!(
+        owner.get(owner.get_root()).map(|node| node.data),
+        Some("chunk")
+    );
+    let (id, _) = find(&world, "anchor");
+    assert_eq!(
+        world.owner(&id).map(|owner| owner.roots()),
+        Some(world.roots())
+    );
+
+    world.resolve_mut(&find(&world, "prop").0).unwrap().data = "crate";
+    assert_eq!(find(&world, "crate").0.mounts(), [anchor, tree]);
+
+    // Detaching the inner scene breaks paths through it
+    let (id, _) = find(&world, "crate");
+    assert!(world.mounted_mut(anchor).unwrap().unmount(tree).is_some());
+    assert!(world.owner(&id).is_none());
+    assert!(world.resolve(&id).is_none());
+}
+
+#[test]
+fn world_transform_combines_through_a_mount() {
+    let (mut world, anchor, chunk, tree) = world_and_chunk();
+    assert!(world.mount(anchor, chunk).is_ok());
+    let (id, traversed) = find(&world, "tree");
+
+    // The mount point's transform times the tree's transform within its
+    // owner matches traversal
+    assert_eq!(id.index(), tree);
+    let mount = world.world_transform(anchor).unwrap();
+    let within = world
+        .owner(&id)
+        .unwrap()
+        .world_transform(id.index())
+        .unwrap();
+    assert_eq!(mount * within, traversed);
+}
+
+#[test]
+fn indices_stay_valid_after_unmount() {
+    let (mut world, anchor, chunk, tree) = world_and_chunk();
+    let chunk_root = chunk.get_root();
+    assert!(world.mount(anchor, chunk).is_ok());
+
+    let moved = world.create_node("moved");
+    world.add_child(world.get_root(), moved);
+
+    let chunk = world.unmount(anchor).unwrap();
+    assert!(world.unmount(anchor).is_none());
+    assert_eq!(chunk.get(chunk_root).map(|node| node.data), Some("chunk"));
+    assert_eq!(chunk.get(tree).map(|node| node.data), Some("tree"));
+    assert_eq!(world.traverse().count(), 3);
+
+    // Remounting elsewhere keeps the same indices
+    assert!(world.mount(moved, chunk).is_ok());
+    let (id, _) = find(&world, "tree");
+    assert_eq!(id.mounts(), [moved]);
+    assert_eq!(id.index(), tree);
+}
+
+#[test]
+fn mount_on_missing_node_hands_the_scene_back() {
+    let (_, _, chunk, tree) = world_and_chunk();
+    let mut world = Scene::new("world");
+
+    let chunk = world.mount(tree, chunk).err().unwrap();
+    assert_eq!(chunk.get(tree).map(|node| node.data), Some("tree"));
+    assert!(world.mounted(tree).is_none());
+}
+
+#[test]
+fn merge_moves_nodes_components_and_mounts() {
+    let (mut world, anchor, mut chunk, tree) = world_and_chunk();
+    assert!(chunk.insert_component(tree, 7_u32).is_ok());
+    assert!(chunk.mount(tree, Scene::new("prop")).is_ok());
+    assert!(world.mount(anchor, chunk).is_ok());
+    let before: Vec<_> = world
+        .traverse()
+        .map(|(_, name, transform)| (*name, transform))
+        .collect();
+
+    let remap = world.merge(anchor).unwrap();
+    assert!(world.mounted(anchor).is_none());
+    assert!(world.merge(anchor).is_none());
+
+    // Traversal is unchanged, but the merged nodes now belong to the world
+    let after: Vec<_> = world
+        .traverse()
+        .map(|(_, name, transform)| (*name, transform))
+        .collect();
+    assert_eq!(before, after);
+    assert!(!find(&world, "tree").0.is_mounted());
+    assert_eq!(find(&world, "prop").0.mounts(), [remap[&tree]]);
+
+    let merged_tree = remap[&tree];
+    assert_eq!(world.get_component::<u32>(merged_tree), Some(&7));
+    assert_eq!(
+        world.world_transform(merged_tree),
+        Some(Matrix4::from_translation(Vector3::new(1.0, 2.0, 0.0)))
+    );
+}
+
+#[test]
+fn unmerge_restores_the_scene() {
+    let (mut world, anchor, mut chunk, tree) = world_and_chunk();
+    assert!(chunk.insert_component(tree, 7_u32).is_ok());
+    assert!(world.mount(anchor, chunk).is_ok());
+    let merged_tree = world.merge(anchor).unwrap()[&tree];
+
+    // Nodes attached beneath merged ones leave along with them
+    let leaf = world.create_node("leaf");
+    world.add_child(merged_tree, leaf);
+    let world_root = world.get_root();
+
+    let (chunk, remap) = world.unmerge(anchor).unwrap();
+    assert!(world.unmerge(anchor).is_none());
+    assert_eq!(world.get_root(), world_root);
+    assert_eq!(world.traverse().count(), 2);
+    assert!(world.get(merged_tree).is_none());
+    assert!(world.get(leaf).is_none());
+    assert_eq!(world.get_component::<u32>(merged_tree), None);
+
+    let tree = remap[&merged_tree];
+    assert_eq!(chunk.get_component::<u32>(tree), Some(&7));
+    let names: Vec<_> = chunk.traverse().map(|(_, name, _)| *name).collect();
+    assert_eq!(names, ["chunk", "tree", "leaf"]);
+
+    // The split out scene can be mounted again like any other
+    assert!(world.mount(anchor, chunk).is_ok());
+    assert_eq!(world.traverse().count(), 5);
+}